@@ -2,6 +2,7 @@ utils: lib: {
   mkProject =
     args@{
       actions ? { },
+      custom ? { },
       meta ? { },
       secrets ? null,
     }:
@@ -18,6 +19,19 @@ utils: lib: {
             else
               "";
           linkSecrets = if secrets != null then "ln -s ${secrets} secrets" else "";
+          linkCustom = builtins.concatStringsSep "\n" (
+            map (name: "ln -s ${custom.${name}.action.${system}}/bin/action ${name}") (
+              builtins.attrNames custom
+            )
+          );
+          customManifest = pkgs.writeText "custom.json" (
+            builtins.toJSON (
+              builtins.mapAttrs (_: value: {
+                description = value.description or "";
+                schema = value.schema or { };
+              }) custom
+            )
+          );
         in
         pkgs.runCommand "actions" { } ''
           mkdir $out
@@ -27,6 +41,8 @@ utils: lib: {
           ${linkAction "end"}
           ${linkAction "webhook"}
           ${linkSecrets}
+          ${linkCustom}
+          ln -s ${customManifest} custom.json
         ''
       );
     };
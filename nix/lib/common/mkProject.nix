@@ -3,6 +3,7 @@ _: lib: {
     {
       api,
       authorizationKeyword,
+      custom ? { },
       deploy,
       description,
       flake,
@@ -63,6 +64,6 @@ _: lib: {
           ++ deploy
         );
       };
-      inherit secrets;
+      inherit custom secrets;
     };
 }
@@ -28,7 +28,16 @@ utils: lib: {
           job_encoded=$(echo -n "''${input[job]}" | jq '@uri' -sRr)
           target_url="${typhonUrl}/evaluation/''${input[evaluation]}/$job_encoded"
           context="Typhon: ''${input[job]}"
-          state="''${input[status]}"
+          case "''${input[status]}" in
+            success|skipped) state="success" ;;
+            pending) state="pending" ;;
+            canceled|infrastructure_error) state="error" ;;
+            *) state="failure" ;;
+          esac
+          # the failures of a quarantined job are not reported
+          if [ "''${input[quarantined]}" == "true" ] && [ "$state" == "failure" ]; then
+            state="success"
+          fi
 
           payload=$(echo 'null' | jq \
             --arg state "$state" \
@@ -1,6 +1,7 @@
 _: lib: {
   mkProject =
     {
+      custom ? { },
       deploy ? [ ],
       instance,
       owner,
@@ -1,6 +1,7 @@
 _: lib: {
   mkProject =
     {
+      custom ? { },
       deploy ? [ ],
       owner,
       repo,
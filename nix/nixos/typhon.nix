@@ -49,6 +49,27 @@ in
           builtins.toString (pkgs.writeText "typhon-password" cfg.hashedPassword);
       description = "Path to a file containing the Argon2id hash of the admin password";
     };
+    publicUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "https://typhon.example.com";
+      description = "The public URL of the instance, used in statuses reported to forges";
+    };
+    buildTimeout = mkOption {
+      type = types.nullOr types.ints.positive;
+      default = null;
+      description = "The default maximum duration of builds, in seconds";
+    };
+    maxSilentTime = mkOption {
+      type = types.nullOr types.ints.positive;
+      default = null;
+      description = "The default maximum duration of builds without any output, in seconds";
+    };
+    maxBuilds = mkOption {
+      type = types.nullOr types.ints.positive;
+      default = null;
+      description = "The maximum number of builds running at once, unlimited by default";
+    };
   };
 
   config = mkIf cfg.enable {
@@ -88,11 +109,20 @@ in
         pkgs.git
         pkgs.bubblewrap
         pkgs.openssh
+        pkgs.util-linux
       ];
       serviceConfig = {
         ExecStart = pkgs.writeShellScript "typhon-start" ''
           cd ${cfg.home}
-          DATABASE_URL="typhon.sqlite" ${cfg.package}/bin/typhon -p "$(cat ${cfg.hashedPasswordFile})" -v
+          DATABASE_URL="typhon.sqlite" ${cfg.package}/bin/typhon -p "$(cat ${cfg.hashedPasswordFile})" ${
+            lib.optionalString (cfg.publicUrl != null) "--public-url ${lib.escapeShellArg cfg.publicUrl}"
+          } ${
+            lib.optionalString (cfg.buildTimeout != null) "--build-timeout ${toString cfg.buildTimeout}"
+          } ${
+            lib.optionalString (cfg.maxSilentTime != null) "--max-silent-time ${toString cfg.maxSilentTime}"
+          } ${
+            lib.optionalString (cfg.maxBuilds != null) "--max-builds ${toString cfg.maxBuilds}"
+          } -v
         '';
         Type = "simple";
         User = "typhon";
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN previous_key;
+ALTER TABLE projects DROP COLUMN previous_key_expiry;
@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN previous_key TEXT;
+ALTER TABLE projects ADD COLUMN previous_key_expiry BIGINT;
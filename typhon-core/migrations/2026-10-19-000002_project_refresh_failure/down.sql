@@ -0,0 +1 @@
+ALTER TABLE projects DROP COLUMN last_refresh_failure;
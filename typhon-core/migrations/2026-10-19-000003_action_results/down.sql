@@ -0,0 +1,2 @@
+ALTER TABLE actions DROP COLUMN exit_code;
+ALTER TABLE actions DROP COLUMN results;
@@ -0,0 +1,2 @@
+ALTER TABLE actions ADD COLUMN exit_code INTEGER;
+ALTER TABLE actions ADD COLUMN results TEXT DEFAULT '{}' NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN sandbox;
+ALTER TABLE actions DROP COLUMN failure;
@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN sandbox TEXT DEFAULT '{}' NOT NULL;
+ALTER TABLE actions ADD COLUMN failure TEXT;
@@ -0,0 +1 @@
+ALTER TABLE projects ADD COLUMN custom_actions TEXT DEFAULT '{}' NOT NULL;
@@ -0,0 +1,12 @@
+CREATE TABLE deliveries (
+    action_id INTEGER NOT NULL REFERENCES actions (id),
+    body TEXT NOT NULL,
+    commands TEXT,
+    headers TEXT NOT NULL,
+    id INTEGER NOT NULL PRIMARY KEY,
+    outcome TEXT,
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    time_created BIGINT NOT NULL,
+    uuid TEXT NOT NULL,
+    UNIQUE (uuid)
+);
@@ -0,0 +1,4 @@
+ALTER TABLE jobsets DROP COLUMN expiry;
+ALTER TABLE jobsets DROP COLUMN kind;
+ALTER TABLE jobsets DROP COLUMN time_active;
+ALTER TABLE jobsets DROP COLUMN time_closed;
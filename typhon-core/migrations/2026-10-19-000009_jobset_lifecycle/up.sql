@@ -0,0 +1,4 @@
+ALTER TABLE jobsets ADD COLUMN expiry BIGINT;
+ALTER TABLE jobsets ADD COLUMN kind TEXT DEFAULT '"Branch"' NOT NULL;
+ALTER TABLE jobsets ADD COLUMN time_active BIGINT DEFAULT 0 NOT NULL;
+ALTER TABLE jobsets ADD COLUMN time_closed BIGINT;
@@ -0,0 +1,5 @@
+ALTER TABLE jobsets DROP COLUMN description;
+ALTER TABLE jobsets DROP COLUMN enabled;
+ALTER TABLE jobsets DROP COLUMN hidden;
+ALTER TABLE jobsets DROP COLUMN keep;
+ALTER TABLE jobsets DROP COLUMN priority;
@@ -0,0 +1,5 @@
+ALTER TABLE jobsets ADD COLUMN description TEXT DEFAULT '' NOT NULL;
+ALTER TABLE jobsets ADD COLUMN enabled BOOL DEFAULT TRUE NOT NULL;
+ALTER TABLE jobsets ADD COLUMN hidden BOOL DEFAULT FALSE NOT NULL;
+ALTER TABLE jobsets ADD COLUMN keep INTEGER;
+ALTER TABLE jobsets ADD COLUMN priority INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,5 @@
+CREATE TABLE run_actions (
+    action_id INTEGER NOT NULL REFERENCES actions (id),
+    id INTEGER NOT NULL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES runs (id)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE builds DROP COLUMN outcome;
+ALTER TABLE jobsets DROP COLUMN checked_jobs;
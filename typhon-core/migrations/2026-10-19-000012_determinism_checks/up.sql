@@ -0,0 +1,2 @@
+ALTER TABLE builds ADD COLUMN outcome TEXT;
+ALTER TABLE jobsets ADD COLUMN checked_jobs TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE jobs DROP COLUMN max_silent_time;
+ALTER TABLE jobs DROP COLUMN timeout;
@@ -0,0 +1,2 @@
+ALTER TABLE jobs ADD COLUMN max_silent_time BIGINT;
+ALTER TABLE jobs ADD COLUMN timeout BIGINT;
@@ -0,0 +1 @@
+ALTER TABLE builds ADD COLUMN failed_dependency TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE jobs DROP COLUMN flaky;
+ALTER TABLE jobs DROP COLUMN max_attempts;
+ALTER TABLE jobsets DROP COLUMN retry;
@@ -0,0 +1,3 @@
+ALTER TABLE jobs ADD COLUMN flaky BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE jobs ADD COLUMN max_attempts INTEGER;
+ALTER TABLE jobsets ADD COLUMN retry TEXT;
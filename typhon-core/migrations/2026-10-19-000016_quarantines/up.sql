@@ -0,0 +1,9 @@
+CREATE TABLE quarantines (
+    id INTEGER NOT NULL PRIMARY KEY,
+    job_name TEXT NOT NULL,
+    jobset_id INTEGER NOT NULL REFERENCES jobsets (id),
+    reason TEXT NOT NULL,
+    time_created BIGINT NOT NULL,
+    time_expires BIGINT NOT NULL,
+    time_lifted BIGINT
+);
@@ -0,0 +1,2 @@
+ALTER TABLE evaluations DROP COLUMN job_counts;
+ALTER TABLE evaluations DROP COLUMN outcome;
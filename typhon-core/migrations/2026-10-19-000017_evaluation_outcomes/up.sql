@@ -0,0 +1,2 @@
+ALTER TABLE evaluations ADD COLUMN job_counts TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE evaluations ADD COLUMN outcome TEXT NOT NULL DEFAULT '"pending"';
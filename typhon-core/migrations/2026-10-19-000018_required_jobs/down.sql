@@ -0,0 +1,3 @@
+ALTER TABLE evaluations DROP COLUMN gate;
+ALTER TABLE jobs DROP COLUMN required;
+ALTER TABLE jobsets DROP COLUMN required_jobs;
@@ -0,0 +1,3 @@
+ALTER TABLE evaluations ADD COLUMN gate TEXT NOT NULL DEFAULT '"pending"';
+ALTER TABLE jobs ADD COLUMN required BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE jobsets ADD COLUMN required_jobs TEXT;
@@ -0,0 +1,7 @@
+CREATE TABLE latest_jobs (
+    id INTEGER NOT NULL PRIMARY KEY,
+    job_id INTEGER NOT NULL REFERENCES jobs (id),
+    job_name TEXT NOT NULL,
+    jobset_id INTEGER NOT NULL REFERENCES jobsets (id),
+    UNIQUE(jobset_id, job_name)
+);
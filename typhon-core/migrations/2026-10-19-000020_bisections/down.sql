@@ -0,0 +1,2 @@
+DROP TABLE bisections;
+ALTER TABLE evaluations DROP COLUMN bisection;
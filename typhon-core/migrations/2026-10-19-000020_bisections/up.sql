@@ -0,0 +1,12 @@
+ALTER TABLE evaluations ADD COLUMN bisection BOOL DEFAULT FALSE NOT NULL;
+CREATE TABLE bisections (
+    id INTEGER NOT NULL PRIMARY KEY,
+    bad_evaluation_id INTEGER NOT NULL REFERENCES evaluations (id),
+    good_evaluation_id INTEGER REFERENCES evaluations (id),
+    job_name TEXT NOT NULL,
+    jobset_id INTEGER NOT NULL REFERENCES jobsets (id),
+    probe_evaluation_id INTEGER REFERENCES evaluations (id),
+    revisions TEXT NOT NULL,
+    time_created BIGINT NOT NULL,
+    time_finished BIGINT
+);
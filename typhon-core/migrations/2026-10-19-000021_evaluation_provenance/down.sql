@@ -0,0 +1,3 @@
+ALTER TABLE evaluations DROP COLUMN flake_lock;
+ALTER TABLE evaluations DROP COLUMN commit_subject;
+ALTER TABLE evaluations DROP COLUMN commit_author;
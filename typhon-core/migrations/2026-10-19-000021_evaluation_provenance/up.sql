@@ -0,0 +1,3 @@
+ALTER TABLE evaluations ADD COLUMN commit_author TEXT;
+ALTER TABLE evaluations ADD COLUMN commit_subject TEXT;
+ALTER TABLE evaluations ADD COLUMN flake_lock TEXT;
@@ -4,31 +4,41 @@ use crate::projects;
 use crate::schema;
 use crate::tasks;
 use crate::Conn;
+use crate::POOL;
 
 use typhon_types::data::TaskStatusKind;
 use typhon_types::*;
 
 use diesel::prelude::*;
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
-use std::iter;
+use std::path::Path;
 use std::process::Stdio;
 use std::str::FromStr;
+use time::OffsetDateTime;
 use tokio::sync::mpsc;
 
 #[derive(Debug, derive_more::Display)]
 pub enum Error {
     #[display("Invalid key")]
     InvalidKey,
+    #[display("Invalid custom actions declaration")]
+    InvalidCustomActions,
     #[display("Wrong secrets format")]
     InvalidSecrets,
     #[display("Outputted non-UTF8 characters")]
     NonUtf8,
+    #[display("Output exceeded the size limit")]
+    OutputTooLarge,
     #[display("Action script not found")]
     ScriptNotFound,
     #[display("Secrets file not found")]
     SecretsNotFound,
+    #[display("Timed out")]
+    TimedOut,
     #[display("Secrets file encrypted with wrong key")]
     WrongRecipient,
     #[display("Unexpected error")]
@@ -36,65 +46,172 @@ pub enum Error {
 }
 
 mod sandboxed_command {
+    use typhon_types::requests::SandboxSettings;
+
     use tokio::process::Command;
-    pub fn new() -> Command {
-        let mut command = Command::new("bwrap");
+
+    pub fn new(settings: &SandboxSettings) -> Command {
+        // resource limits are set with `prlimit`, they are inherited by `bwrap`
+        // and the action
+        let mut command = if settings.cpu.is_some() || settings.memory.is_some() {
+            let mut command = Command::new("prlimit");
+            if let Some(cpu) = settings.cpu {
+                command.arg(format!("--cpu={cpu}"));
+            }
+            if let Some(memory) = settings.memory {
+                command.arg(format!("--as={memory}"));
+            }
+            command.arg("bwrap");
+            command
+        } else {
+            Command::new("bwrap")
+        };
         command
             .kill_on_drop(true)
             .args(["--proc", "/proc"])
             .args(["--dev", "/dev"])
             .args(["--ro-bind", "/nix/store", "/nix/store"])
             .args(["--ro-bind", "/nix/var/nix", "/nix/var/nix"])
-            .args(["--ro-bind", "/etc/resolv.conf", "/etc/resolv.conf"])
-            .arg("--clearenv")
-            .arg("--unshare-pid");
+            .args(["--ro-bind", "/etc/resolv.conf", "/etc/resolv.conf"]);
+        for bind in &settings.binds {
+            command.args(["--ro-bind", bind, bind]);
+        }
+        command.arg("--clearenv");
+        for var in &settings.env {
+            if let Ok(value) = std::env::var(var) {
+                command.args(["--setenv", var, &value]);
+            }
+        }
+        if !settings.network.unwrap_or(true) {
+            command.arg("--unshare-net");
+        }
+        // the sandboxed processes are killed with `bwrap`, e.g. on timeout
+        command.args(["--unshare-pid", "--die-with-parent"]);
         command
     }
 }
 
-async fn action(
-    project: &projects::Project,
-    path: &String,
-    name: &String,
-    input: &Value,
-    sender: mpsc::UnboundedSender<String>,
-) -> Result<String, Error> {
-    use tokio::io::AsyncBufReadExt;
-    use tokio::io::AsyncReadExt;
-    use tokio::io::AsyncWriteExt;
-    use tokio::io::BufReader;
+/// The identities allowed to decrypt the secrets of a project: its current
+/// key and, during the grace period of a rotation, its previous key.
+pub fn identities(project: &models::Project) -> Result<Vec<age::x25519::Identity>, Error> {
+    let mut keys =
+        vec![age::x25519::Identity::from_str(&project.key).map_err(|_| Error::InvalidKey)?];
+    if let (Some(key), Some(expiry)) = (&project.previous_key, project.previous_key_expiry) {
+        if OffsetDateTime::now_utc().unix_timestamp() < expiry {
+            keys.push(age::x25519::Identity::from_str(key).map_err(|_| Error::InvalidKey)?);
+        }
+    }
+    Ok(keys)
+}
 
-    let key =
-        age::x25519::Identity::from_str(&project.project.key).map_err(|_| Error::InvalidKey)?;
+/// Decrypts and parses the `secrets` file of an actions path. A missing file
+/// stands for an empty set of secrets.
+pub fn secrets(project: &models::Project, path: &str) -> Result<Value, Error> {
+    let keys = identities(project)?;
 
-    let decrypted = File::open(&format!("{}/secrets", path))
+    let decrypted = File::open(format!("{}/secrets", path))
         .map(|encrypted| {
-            let decryptor =
-                match age::Decryptor::new(&encrypted).map_err(|_| Error::InvalidSecrets)? {
-                    age::Decryptor::Recipients(d) => d,
-                    _ => unreachable!(),
-                };
+            let decryptor = match age::Decryptor::new(age::armor::ArmoredReader::new(encrypted))
+                .map_err(|_| Error::InvalidSecrets)?
+            {
+                age::Decryptor::Recipients(d) => d,
+                _ => Err(Error::InvalidSecrets)?,
+            };
 
             let mut decrypted = String::new();
             let mut reader = decryptor
-                .decrypt(iter::once(&key as &dyn age::Identity))
+                .decrypt(keys.iter().map(|key| key as &dyn age::Identity))
                 .map_err(|e| match e {
                     age::DecryptError::NoMatchingKeys => Error::WrongRecipient,
                     _ => Error::InvalidSecrets,
                 })?;
-            let _ = reader.read_to_string(&mut decrypted);
+            reader
+                .read_to_string(&mut decrypted)
+                .map_err(|_| Error::InvalidSecrets)?;
 
             Ok(decrypted)
         })
         .unwrap_or(Ok::<String, Error>("{}".to_string()))?;
-    let secrets: Value = serde_json::from_str(&decrypted).map_err(|_| Error::InvalidSecrets)?;
+
+    serde_json::from_str(&decrypted).map_err(|_| Error::InvalidSecrets)
+}
+
+/// Names that custom actions cannot take
+const RESERVED_NAMES: [&str; 5] = ["begin", "end", "jobsets", "secrets", "webhook"];
+
+/// Reads and checks the `custom.json` file of an actions path, which declares
+/// the custom actions of a project. A missing file stands for no custom
+/// actions.
+pub fn custom_actions(path: &str) -> Result<BTreeMap<String, responses::CustomAction>, Error> {
+    let custom_actions: BTreeMap<String, responses::CustomAction> =
+        match File::open(format!("{}/custom.json", path)) {
+            Ok(file) => serde_json::from_reader(file).map_err(|_| Error::InvalidCustomActions)?,
+            Err(_) => BTreeMap::new(),
+        };
+    for (name, custom_action) in custom_actions.iter() {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            && !RESERVED_NAMES.contains(&name.as_str());
+        if !valid_name
+            || !Path::new(&format!("{}/{}", path, name)).exists()
+            || jsonschema::JSONSchema::compile(&custom_action.schema).is_err()
+        {
+            return Err(Error::InvalidCustomActions);
+        }
+    }
+    Ok(custom_actions)
+}
+
+/// The maximum size, in bytes, of the standard output of an action
+const MAX_STDOUT_SIZE: u64 = 1 << 20;
+
+const SIGXCPU: i32 = 24;
+
+/// Structured results are reported by actions on their standard error, one
+/// per line, prefixed with `@typhon`.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ResultMessage {
+    Annotation(responses::ActionAnnotation),
+    Link(responses::ActionLink),
+    Summary(String),
+}
+
+pub struct Output {
+    pub exit_code: Option<i32>,
+    pub failure: Option<responses::ActionFailure>,
+    pub results: responses::ActionResults,
+    pub stdout: String,
+}
+
+async fn action(
+    project: &projects::Project,
+    path: &String,
+    name: &String,
+    input: &Value,
+    sender: mpsc::UnboundedSender<String>,
+) -> Result<Output, Error> {
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+
+    if !std::path::Path::new(&format!("{}/{}", path, name)).is_file() {
+        return Err(Error::ScriptNotFound);
+    }
+
+    let secrets = secrets(&project.project, path)?;
 
     let action_input = json!({
         "input": input,
         "secrets": secrets,
     });
 
-    let mut child = sandboxed_command::new()
+    let settings = project.sandbox().settings(name);
+
+    let mut child = sandboxed_command::new(&settings)
         .arg(&format!("{}/{}", path, name))
         .stdin(Stdio::piped())
         .stderr(Stdio::piped())
@@ -102,7 +219,7 @@ async fn action(
         .spawn()
         .expect("command bwrap failed to start");
     let mut stdin = child.stdin.take().ok_or(Error::Unexpected)?;
-    let mut stdout = child.stdout.take().ok_or(Error::Unexpected)?;
+    let stdout = child.stdout.take().ok_or(Error::Unexpected)?;
     let stderr = child.stderr.take().ok_or(Error::Unexpected)?;
     stdin
         .write(action_input.to_string().as_bytes())
@@ -110,19 +227,100 @@ async fn action(
         .map_err(|_| Error::Unexpected)?;
     drop(stdin); // send EOF
 
-    let buffer = BufReader::new(stderr);
-    let mut lines = buffer.lines();
-    while let Some(line) = lines.next_line().await.unwrap() {
-        let _ = sender.send(line);
-    }
+    let read_stderr = async {
+        let mut results = responses::ActionResults::default();
+        let mut lines = BufReader::new(stderr).lines();
+        while let Some(line) = lines.next_line().await.map_err(|_| Error::NonUtf8)? {
+            match line
+                .strip_prefix("@typhon ")
+                .and_then(|message| serde_json::from_str(message).ok())
+            {
+                Some(ResultMessage::Annotation(annotation)) => results.annotations.push(annotation),
+                // links are shown on public pages, other schemes could run scripts
+                Some(ResultMessage::Link(link))
+                    if link.url.starts_with("https://") || link.url.starts_with("http://") =>
+                {
+                    results.links.push(link)
+                }
+                Some(ResultMessage::Link(_)) => (),
+                Some(ResultMessage::Summary(summary)) => results.summary = Some(summary),
+                None => {
+                    let _ = sender.send(line);
+                }
+            }
+        }
+        Ok(results)
+    };
 
-    let mut res = String::new();
-    stdout
-        .read_to_string(&mut res)
-        .await
-        .map_err(|_| Error::NonUtf8)?;
+    let read_stdout = async {
+        let mut res = Vec::new();
+        stdout
+            .take(MAX_STDOUT_SIZE + 1)
+            .read_to_end(&mut res)
+            .await
+            .map_err(|_| Error::Unexpected)?;
+        if res.len() as u64 > MAX_STDOUT_SIZE {
+            return Err(Error::OutputTooLarge);
+        }
+        String::from_utf8(res).map_err(|_| Error::NonUtf8)
+    };
+
+    let run = async {
+        let (results, stdout) = tokio::try_join!(read_stderr, read_stdout)?;
+        let exit_code = child.wait().await.map_err(|_| Error::Unexpected)?.code();
+        Ok::<_, Error>((results, stdout, exit_code))
+    };
+    let (results, stdout, exit_code) = match settings.timeout {
+        Some(timeout) => tokio::time::timeout(std::time::Duration::from_secs(timeout), run)
+            .await
+            .map_err(|_| Error::TimedOut)??,
+        None => run.await?,
+    };
+
+    // `bwrap` reports the death of the action by a signal with the shell
+    // convention, and exceeding the CPU time limit raises `SIGXCPU`. Under a
+    // memory limit, allocations fail and nothing tells it apart from other
+    // failures, which are then reported as such.
+    let failure = if settings.cpu.is_some() && exit_code == Some(128 + SIGXCPU) {
+        Some(responses::ActionFailure::CpuLimitExceeded)
+    } else {
+        None
+    };
 
-    Ok(res)
+    Ok(Output {
+        exit_code,
+        failure,
+        results,
+        stdout,
+    })
+}
+
+impl models::Action {
+    pub fn failure(&self) -> Option<responses::ActionFailure> {
+        self.failure
+            .as_ref()
+            .and_then(|failure| serde_json::from_str(failure).ok())
+    }
+    pub fn results(&self) -> responses::ActionResults {
+        serde_json::from_str(&self.results).unwrap_or_default()
+    }
+}
+
+impl From<&error::Error> for responses::ActionFailure {
+    fn from(e: &error::Error) -> Self {
+        use responses::ActionFailure;
+        match e {
+            error::Error::ActionError(Error::TimedOut) => ActionFailure::TimedOut,
+            error::Error::ActionError(Error::OutputTooLarge) => ActionFailure::OutputTooLarge,
+            error::Error::ActionError(
+                Error::InvalidKey
+                | Error::InvalidSecrets
+                | Error::SecretsNotFound
+                | Error::WrongRecipient,
+            ) => ActionFailure::InvalidSecrets,
+            _ => ActionFailure::Internal,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -156,10 +354,13 @@ impl Action {
     pub fn info(&self) -> responses::ActionInfo {
         responses::ActionInfo {
             handle: self.handle(),
+            exit_code: self.action.exit_code,
+            failure: self.action.failure(),
             input: self.action.input.clone(),
             name: self.action.name.clone(),
             path: self.action.path.clone(),
             project: handles::project(self.project.name.clone()),
+            results: self.action.results(),
             status: self.task.status(),
         }
     }
@@ -190,20 +391,50 @@ impl Action {
         };
 
         let finish = {
-            let handle = self.handle();
-            move |res: Option<Result<String, error::Error>>| {
+            let self_ = self.clone();
+            move |res: Option<Result<Output, error::Error>>| {
                 let status = match res {
-                    Some(Err(_)) => {
+                    // a project without this action skips it
+                    Some(Err(error::Error::ActionError(Error::ScriptNotFound))) => {
                         let _ = finish(None);
-                        TaskStatusKind::Failure
+                        TaskStatusKind::Skipped
+                    }
+                    Some(Err(e)) => {
+                        if let Err(e) = self_.record_failure((&e).into()) {
+                            tracing::error!(
+                                "failed to record failure of action {}: {}",
+                                self_.handle(),
+                                e
+                            );
+                        }
+                        let _ = finish(None);
+                        match e {
+                            error::Error::ActionError(Error::TimedOut) => TaskStatusKind::TimedOut,
+                            e if e.is_internal() => TaskStatusKind::InfrastructureError,
+                            _ => TaskStatusKind::Failure,
+                        }
+                    }
+                    Some(Ok(output)) => {
+                        if let Err(e) = self_.record_output(&output) {
+                            tracing::error!(
+                                "failed to record output of action {}: {}",
+                                self_.handle(),
+                                e
+                            );
+                        }
+                        if output.exit_code == Some(0) {
+                            finish(Some(output.stdout))
+                        } else {
+                            let _ = finish(None);
+                            TaskStatusKind::Failure
+                        }
                     }
-                    Some(Ok(stdout)) => finish(Some(stdout)),
                     None => {
                         let _ = finish(None);
                         TaskStatusKind::Canceled
                     }
                 };
-                (status, Event::ActionFinished(handle))
+                (status, Event::ActionFinished(self_.handle()))
             }
         };
 
@@ -213,6 +444,29 @@ impl Action {
 
         Ok(())
     }
+
+    fn record_output(&self, output: &Output) -> Result<(), error::Error> {
+        let mut conn = POOL.get().unwrap();
+        diesel::update(&self.action)
+            .set((
+                schema::actions::exit_code.eq(output.exit_code),
+                schema::actions::failure.eq(output
+                    .failure
+                    .as_ref()
+                    .map(|failure| serde_json::to_string(failure).unwrap())),
+                schema::actions::results.eq(serde_json::to_string(&output.results).unwrap()),
+            ))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    fn record_failure(&self, failure: responses::ActionFailure) -> Result<(), error::Error> {
+        let mut conn = POOL.get().unwrap();
+        diesel::update(&self.action)
+            .set(schema::actions::failure.eq(serde_json::to_string(&failure).unwrap()))
+            .execute(&mut conn)?;
+        Ok(())
+    }
 }
 
 pub mod webhooks {
@@ -230,6 +484,27 @@ pub mod webhooks {
         pub body: String,
     }
 
+    /// Headers carrying the webhook secret, or a signature made with it
+    const SECRET_HEADERS: [&str; 5] = [
+        "x-gitlab-token",
+        "x-hub-signature",
+        "x-gitea-signature",
+        "x-forgejo-signature",
+        "x-gogs-signature",
+    ];
+
+    impl Input {
+        /// Removes the headers derived from the webhook secret, which must not
+        /// be logged, stored or passed to the action, as its input is public
+        pub fn without_secrets(mut self) -> Self {
+            self.headers.retain(|name, _| {
+                let name = name.to_lowercase();
+                !SECRET_HEADERS.iter().any(|header| name.starts_with(header))
+            });
+            self
+        }
+    }
+
     #[derive(Clone, Deserialize)]
     #[serde(tag = "command")]
     pub enum Command {
@@ -0,0 +1,313 @@
+use crate::error::Error;
+use crate::evaluations;
+use crate::forges;
+use crate::jobsets::Jobset;
+use crate::models;
+use crate::schema;
+use crate::tasks;
+use crate::Conn;
+use crate::{handles, responses};
+use crate::{log_event, Event};
+use crate::{EVENT_LOGGER, POOL, RUNTIME};
+
+use typhon_types::data::TaskStatusKind;
+
+use diesel::prelude::*;
+use std::str::FromStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// The behaviour of a job in a tested evaluation
+enum Verdict {
+    Bad,
+    Good,
+    /// The job could not be built, e.g. because the evaluation failed
+    Skip,
+}
+
+fn evaluation(conn: &mut Conn, id: i32) -> Result<models::Evaluation, Error> {
+    Ok(schema::evaluations::table.find(id).first(conn)?)
+}
+
+fn evaluation_handle(conn: &mut Conn, id: i32) -> Result<handles::Evaluation, Error> {
+    let uuid = evaluation(conn, id)?.uuid;
+    Ok(handles::evaluation(Uuid::from_str(&uuid).unwrap()))
+}
+
+/// The verdict for a job in an evaluation, `None` while it is pending
+fn verdict(
+    conn: &mut Conn,
+    project: &models::Project,
+    evaluation_id: i32,
+    job: &str,
+) -> Result<Option<Verdict>, Error> {
+    let (evaluation, task) = schema::evaluations::table
+        .inner_join(schema::tasks::table)
+        .filter(schema::evaluations::id.eq(evaluation_id))
+        .first::<(models::Evaluation, models::Task)>(conn)?;
+    let evaluation = evaluations::Evaluation {
+        project: project.clone(),
+        evaluation,
+        task: tasks::Task { task },
+    };
+    match evaluation.task.status_kind() {
+        TaskStatusKind::Pending => return Ok(None),
+        TaskStatusKind::Success => (),
+        _ => return Ok(Some(Verdict::Skip)),
+    }
+    let jobs = evaluations::Evaluation::jobs(
+        &handles::project(project.name.clone()),
+        &evaluation.handle(),
+        evaluation.evaluation.id,
+        Some(job.to_string()),
+        conn,
+    )?;
+    let kind = jobs
+        .get(job)
+        .map(|job| TaskStatusKind::from(&responses::TaskStatus::from(job)));
+    Ok(match kind {
+        Some(TaskStatusKind::Pending) => None,
+        Some(TaskStatusKind::Success) => Some(Verdict::Good),
+        Some(kind) if kind.is_failure() => Some(Verdict::Bad),
+        _ => Some(Verdict::Skip),
+    })
+}
+
+/// The revisions strictly between two locked URLs, the oldest first. Fails
+/// if the project's forge cannot list them all.
+fn revisions(jobset: &Jobset, good: &str, bad: &str) -> Result<Vec<String>, Error> {
+    let unlisted = |reason: String| Error::RevisionsNotListed(jobset.handle(), reason);
+    let Some(forge) = forges::forge(&jobset.project) else {
+        return Err(unlisted("the project has no forge".to_string()));
+    };
+    let (Some(base), Some(head)) = (forges::revision(good), forges::revision(bad)) else {
+        return Err(unlisted(
+            "the evaluations are not locked to revisions".to_string(),
+        ));
+    };
+    let client = reqwest::Client::new();
+    let mut revisions = RUNTIME
+        .block_on(forges::revisions(&client, &forge, base, head))
+        .map_err(|e| unlisted(e.to_string()))?;
+    revisions.retain(|revision| revision != head);
+    Ok(revisions)
+}
+
+impl models::Bisection {
+    fn revisions(&self) -> Vec<String> {
+        serde_json::from_str(&self.revisions).unwrap_or_default()
+    }
+
+    pub fn info(&self, conn: &mut Conn) -> Result<responses::Bisection, Error> {
+        Ok(responses::Bisection {
+            job: self.job_name.clone(),
+            first_bad: evaluation_handle(conn, self.bad_evaluation_id)?,
+            last_good: self
+                .good_evaluation_id
+                .map(|id| evaluation_handle(conn, id))
+                .transpose()?,
+            probe: self
+                .probe_evaluation_id
+                .map(|id| evaluation_handle(conn, id))
+                .transpose()?,
+            remaining: self.revisions().len() as u32,
+            time_created: OffsetDateTime::from_unix_timestamp(self.time_created)?,
+            time_finished: self
+                .time_finished
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+        })
+    }
+
+    /// Judges the revision being tested, then evaluates the next one, until
+    /// an evaluation is pending or there is nothing left to test
+    fn step(mut self, conn: &mut Conn, jobset: &Jobset) -> Result<Self, Error> {
+        let mut revisions = self.revisions();
+        let mut changed = false;
+        loop {
+            let mid = revisions.len() / 2;
+            if let Some(probe) = self.probe_evaluation_id {
+                match verdict(conn, &jobset.project, probe, &self.job_name)? {
+                    None if changed => break,
+                    None => return Ok(self),
+                    Some(Verdict::Bad) => {
+                        self.bad_evaluation_id = probe;
+                        revisions.truncate(mid);
+                    }
+                    Some(Verdict::Good) => {
+                        self.good_evaluation_id = Some(probe);
+                        revisions.drain(..=mid);
+                    }
+                    Some(Verdict::Skip) => {
+                        revisions.remove(mid);
+                    }
+                }
+                self.probe_evaluation_id = None;
+                changed = true;
+                continue;
+            }
+            changed = true;
+            let url = evaluation(conn, self.bad_evaluation_id)?.url;
+            let Some(url) = revisions
+                .get(mid)
+                .and_then(|revision| forges::at_revision(&url, revision))
+            else {
+                revisions.clear();
+                self.time_finished = Some(OffsetDateTime::now_utc().unix_timestamp());
+                break;
+            };
+            match jobset.evaluate_revision(conn, &url) {
+                Ok(probe) => self.probe_evaluation_id = Some(probe.evaluation.id),
+                Err(Error::NixError(e)) => {
+                    tracing::warn!("failed to lock {} for a bisection: {}", url, e);
+                    revisions.remove(mid);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.revisions = serde_json::to_string(&revisions).unwrap();
+        diesel::update(schema::bisections::table.find(self.id))
+            .set((
+                schema::bisections::bad_evaluation_id.eq(self.bad_evaluation_id),
+                schema::bisections::good_evaluation_id.eq(self.good_evaluation_id),
+                schema::bisections::probe_evaluation_id.eq(self.probe_evaluation_id),
+                schema::bisections::revisions.eq(&self.revisions),
+                schema::bisections::time_finished.eq(self.time_finished),
+            ))
+            .execute(conn)?;
+        log_event(Event::BisectionUpdated(jobset.handle()));
+        Ok(self)
+    }
+}
+
+/// The latest bisection of a job of a jobset
+pub fn latest(
+    conn: &mut Conn,
+    jobset: &Jobset,
+    job: &str,
+) -> Result<Option<models::Bisection>, Error> {
+    Ok(schema::bisections::table
+        .filter(schema::bisections::jobset_id.eq(jobset.jobset.id))
+        .filter(schema::bisections::job_name.eq(job))
+        .order(schema::bisections::id.desc())
+        .first(conn)
+        .optional()?)
+}
+
+/// Starts looking for the first evaluation where a job started failing,
+/// unless a bisection of the job is already running
+pub fn start(conn: &mut Conn, jobset: &Jobset, job: &str) -> Result<models::Bisection, Error> {
+    if let Some(bisection) = latest(conn, jobset, job)? {
+        if bisection.time_finished.is_none() {
+            return Ok(bisection);
+        }
+    }
+
+    let ids = schema::evaluations::table
+        .inner_join(schema::jobs::table)
+        .filter(schema::evaluations::project_id.eq(jobset.project.id))
+        .filter(schema::evaluations::jobset_name.eq(&jobset.jobset.name))
+        .filter(schema::evaluations::bisection.eq(false))
+        .filter(schema::jobs::name.eq(job))
+        .order(schema::evaluations::time_created.desc())
+        .select(schema::evaluations::id)
+        .load::<i32>(conn)?;
+    let (mut bad, mut good) = (None, None);
+    for id in ids {
+        match verdict(conn, &jobset.project, id, job)? {
+            Some(Verdict::Bad) => bad = Some(id),
+            Some(Verdict::Good) => {
+                good = Some(id);
+                break;
+            }
+            _ => (),
+        }
+    }
+    let Some(bad) = bad else {
+        return Err(Error::JobNotFailing(jobset.handle(), job.to_string()));
+    };
+
+    let revisions = match good {
+        Some(good) => {
+            let good = evaluation(conn, good)?.url;
+            let bad = evaluation(conn, bad)?.url;
+            self::revisions(jobset, &good, &bad)?
+        }
+        None => Vec::new(),
+    };
+    let bisection = diesel::insert_into(schema::bisections::table)
+        .values(models::NewBisection {
+            bad_evaluation_id: bad,
+            good_evaluation_id: good,
+            job_name: job,
+            jobset_id: jobset.jobset.id,
+            revisions: &serde_json::to_string(&revisions).unwrap(),
+            time_created: OffsetDateTime::now_utc().unix_timestamp(),
+        })
+        .get_result::<models::Bisection>(conn)?;
+    bisection.step(conn, jobset)
+}
+
+fn update(conn: &mut Conn, bisection: models::Bisection) -> Result<(), Error> {
+    let (jobset, project) = schema::jobsets::table
+        .inner_join(schema::projects::table)
+        .filter(schema::jobsets::id.eq(bisection.jobset_id))
+        .first(conn)?;
+    bisection.step(conn, &Jobset { jobset, project })?;
+    Ok(())
+}
+
+/// Moves bisections forward as the evaluations they test finish. Running
+/// bisections are updated first, in case events were missed.
+pub fn listen() {
+    use futures_core::stream::Stream;
+
+    let Some(events) = EVENT_LOGGER.listen() else {
+        return;
+    };
+    RUNTIME.spawn(async move {
+        let res = tokio::task::spawn_blocking(|| {
+            let mut conn = POOL.get().unwrap();
+            let bisections = schema::bisections::table
+                .filter(schema::bisections::time_finished.is_null())
+                .load::<models::Bisection>(&mut conn)?;
+            for bisection in bisections {
+                update(&mut conn, bisection)?;
+            }
+            Ok::<_, Error>(())
+        })
+        .await;
+        if let Ok(Err(e)) = res {
+            tracing::error!("failed to update running bisections: {}", e);
+        }
+        let mut events = std::pin::pin!(events);
+        while let Some(event) = std::future::poll_fn(|cx| events.as_mut().poll_next(cx)).await {
+            let handle = match event {
+                Event::RunUpdated(handle) => handle.job.evaluation,
+                Event::EvaluationFinished(handle) => handle,
+                _ => continue,
+            };
+            let res = tokio::task::spawn_blocking(move || {
+                let mut conn = POOL.get().unwrap();
+                let bisections = schema::bisections::table
+                    .inner_join(
+                        schema::evaluations::table.on(schema::evaluations::id
+                            .nullable()
+                            .eq(schema::bisections::probe_evaluation_id)),
+                    )
+                    .filter(schema::evaluations::uuid.eq(handle.uuid.as_hyphenated().to_string()))
+                    .filter(schema::bisections::time_finished.is_null())
+                    .select(models::Bisection::as_select())
+                    .load::<models::Bisection>(&mut conn)?;
+                for bisection in bisections {
+                    update(&mut conn, bisection)?;
+                }
+                Ok::<_, Error>(())
+            })
+            .await;
+            if let Ok(Err(e)) = res {
+                tracing::error!("failed to update a bisection: {}", e);
+            }
+        }
+    });
+}
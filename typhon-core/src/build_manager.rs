@@ -7,21 +7,23 @@ use crate::nix::DrvPath;
 use crate::schema;
 use crate::tasks;
 use crate::Conn;
+use crate::Settings;
 use crate::POOL;
 use crate::RUNTIME;
 
 use typhon_types::{data::TaskStatusKind, *};
 
 use diesel::prelude::*;
-use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{LazyLock, Mutex};
 use time::OffsetDateTime;
 use tokio::{
     sync::{mpsc, oneshot, watch},
     task::JoinSet,
 };
 
-type Output = Option<Option<()>>;
+type Output = TaskStatusKind;
 
 pub struct BuildHandle {
     pub abort: oneshot::Sender<()>,
@@ -38,7 +40,7 @@ impl BuildHandle {
 
 enum Msg {
     Abort(DrvPath),
-    Build(DrvPath, oneshot::Sender<BuildHandle>),
+    Build(DrvPath, nix::BuildOptions, oneshot::Sender<BuildHandle>),
     Finished(DrvPath, Output),
     Shutdown,
 }
@@ -67,6 +69,7 @@ impl State {
     async fn new_build(
         &mut self,
         drv: DrvPath,
+        options: nix::BuildOptions,
         sender: &mpsc::UnboundedSender<Msg>,
         abort_receiver: oneshot::Receiver<()>,
         res_sender: oneshot::Sender<Output>,
@@ -111,17 +114,24 @@ impl State {
         };
         self.join_set.spawn(abort);
 
+        let settings = Settings::get();
+        let options = nix::BuildOptions {
+            timeout: options.timeout.or(settings.build_timeout),
+            max_silent_time: options.max_silent_time.or(settings.max_silent_time),
+            ..options
+        };
         let run = {
             let drv = drv.clone();
             let sender = sender.clone();
-            move |sender_log| run_build(drv, sender, sender_log)
+            move |sender_log| run_build(drv, options, sender, sender_log)
         };
         let finish = {
             let drv = drv.clone();
             let handle = build.handle();
+            let id = build.build.id;
             let sender = sender.clone();
-            |res| {
-                let status = finish_build(drv, sender, res);
+            move |res| {
+                let status = finish_build(drv, id, sender, res);
                 (status, Event::BuildFinished(handle))
             }
         };
@@ -131,45 +141,226 @@ impl State {
     }
 }
 
-fn finish_build(drv: DrvPath, sender: mpsc::UnboundedSender<Msg>, res: Output) -> TaskStatusKind {
-    let _ = sender.send(Msg::Finished(drv, res.clone()));
-    match res {
-        Some(Some(())) => TaskStatusKind::Success,
-        Some(None) => TaskStatusKind::Failure,
-        None => TaskStatusKind::Canceled,
+/// A build waiting for a slot, ordered by priority, then first come first
+/// served
+struct Waiting {
+    priority: i32,
+    order: Reverse<u64>,
+    sender: oneshot::Sender<Slot>,
+}
+
+impl PartialEq for Waiting {
+    fn eq(&self, other: &Self) -> bool {
+        (self.priority, self.order) == (other.priority, other.order)
+    }
+}
+
+impl Eq for Waiting {}
+
+impl PartialOrd for Waiting {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiting {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.priority, self.order).cmp(&(other.priority, other.order))
+    }
+}
+
+#[derive(Default)]
+struct SlotsState {
+    /// The number of builds that can still start, unlimited if `None`
+    free: Option<usize>,
+    count: u64,
+    waiting: BinaryHeap<Waiting>,
+}
+
+/// Limits the number of `nix build` running at once
+struct Slots(Mutex<SlotsState>);
+
+/// The permission to run a build, given back when dropped
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        SLOTS.release();
+    }
+}
+
+impl Slots {
+    fn new() -> Self {
+        Self(Mutex::new(SlotsState {
+            free: Settings::get().max_builds,
+            ..SlotsState::default()
+        }))
+    }
+
+    async fn acquire(&self, priority: i32) -> Slot {
+        let receiver = {
+            let mut state = self.0.lock().unwrap();
+            match &mut state.free {
+                None => return Slot,
+                Some(free) if *free > 0 => {
+                    *free -= 1;
+                    return Slot;
+                }
+                Some(_) => (),
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.count += 1;
+            let order = Reverse(state.count);
+            state.waiting.push(Waiting {
+                priority,
+                order,
+                sender,
+            });
+            receiver
+        };
+        // waiting builds are only removed from the queue by `release`, which
+        // sends them a slot
+        receiver.await.unwrap()
+    }
+
+    fn release(&self) {
+        let mut state = self.0.lock().unwrap();
+        while let Some(waiting) = state.waiting.pop() {
+            match waiting.sender.send(Slot) {
+                Ok(()) => return,
+                // the build was canceled while waiting, try the next one
+                Err(slot) => std::mem::forget(slot),
+            }
+        }
+        if let Some(free) = &mut state.free {
+            *free += 1;
+        }
     }
 }
 
+static SLOTS: LazyLock<Slots> = LazyLock::new(Slots::new);
+
+/// Why a build did not produce its outputs.
+enum BuildError {
+    Nix(nix::Error),
+    /// The build of a dependency, identified by its id, did not succeed
+    DependencyFailed(i32),
+    /// Typhon lost track of a dependency
+    Internal,
+}
+
+impl From<nix::Error> for BuildError {
+    fn from(e: nix::Error) -> BuildError {
+        BuildError::Nix(e)
+    }
+}
+
+fn finish_build(
+    drv: DrvPath,
+    id: i32,
+    sender: mpsc::UnboundedSender<Msg>,
+    res: Option<Result<responses::BuildOutcome, BuildError>>,
+) -> TaskStatusKind {
+    let mut conn = POOL.get().unwrap();
+    match &res {
+        Some(Ok(outcome)) => {
+            let res = diesel::update(schema::builds::table.find(id))
+                .set(schema::builds::outcome.eq(serde_json::to_string(outcome).unwrap()))
+                .execute(&mut conn);
+            if let Err(e) = res {
+                tracing::error!("failed to record the outcome of build {}: {}", id, e);
+            }
+        }
+        Some(Err(BuildError::DependencyFailed(dependency_id))) => {
+            let res = schema::builds::table
+                .find(dependency_id)
+                .select(schema::builds::uuid)
+                .first::<String>(&mut conn)
+                .and_then(|uuid| {
+                    diesel::update(schema::builds::table.find(id))
+                        .set(schema::builds::failed_dependency.eq(uuid))
+                        .execute(&mut conn)
+                });
+            if let Err(e) = res {
+                tracing::error!(
+                    "failed to record the failed dependency of build {}: {}",
+                    id,
+                    e
+                );
+            }
+        }
+        _ => (),
+    }
+    let status = match res {
+        // a non-deterministic build fails, even though its outputs are usable
+        Some(Ok(responses::BuildOutcome::NonDeterministic)) => TaskStatusKind::Failure,
+        Some(Ok(_)) => TaskStatusKind::Success,
+        Some(Err(BuildError::Nix(nix::Error::BuildFailed | nix::Error::NonDeterministic))) => {
+            TaskStatusKind::Failure
+        }
+        Some(Err(BuildError::Nix(nix::Error::TimedOut))) => TaskStatusKind::TimedOut,
+        Some(Err(BuildError::Nix(e))) => {
+            tracing::error!("infrastructure error during build {}: {:?}", id, e);
+            TaskStatusKind::InfrastructureError
+        }
+        Some(Err(BuildError::DependencyFailed(_))) => TaskStatusKind::DependencyFailed,
+        Some(Err(BuildError::Internal)) => TaskStatusKind::InfrastructureError,
+        None => TaskStatusKind::Canceled,
+    };
+    let _ = sender.send(Msg::Finished(drv, status));
+    status
+}
+
 async fn run_build(
     drv: DrvPath,
+    options: nix::BuildOptions,
     sender: mpsc::UnboundedSender<Msg>,
     sender_log: mpsc::UnboundedSender<String>,
-) -> Option<()> {
+) -> Result<responses::BuildOutcome, BuildError> {
+    if options.check {
+        let _slot = SLOTS.acquire(options.priority).await;
+        return match nix::realise(&drv, &options, sender_log).await {
+            Ok(_) => Ok(responses::BuildOutcome::Deterministic),
+            Err(nix::Error::NonDeterministic) => Ok(responses::BuildOutcome::NonDeterministic),
+            Err(e) => Err(e.into()),
+        };
+    }
     if nix::is_cached(&drv).await == Ok(false) {
-        let json: serde_json::Value = nix::derivation_json(&nix::Expr::Path(drv.to_string()))
-            .await
-            .ok()?;
+        let json: serde_json::Value =
+            nix::derivation_json(&nix::Expr::Path(drv.to_string())).await?;
         let input_drvs = json[&drv.to_string()]["inputDrvs"].as_object().unwrap();
         let mut handle_receivers: Vec<oneshot::Receiver<BuildHandle>> = Vec::new();
         for (drv, _) in input_drvs {
             let (handle_sender, handle_receiver) = oneshot::channel();
-            let _ = sender.send(Msg::Build(DrvPath::new(drv), handle_sender));
+            let _ = sender.send(Msg::Build(
+                DrvPath::new(drv),
+                nix::BuildOptions {
+                    priority: options.priority,
+                    ..nix::BuildOptions::default()
+                },
+                handle_sender,
+            ));
             handle_receivers.push(handle_receiver);
         }
         let mut join_set = JoinSet::new();
         for handle_receiver in handle_receivers.drain(..) {
             join_set.spawn(async move {
-                let _ = handle_receiver.await.unwrap().wait().await; // FIXME
+                let handle = handle_receiver.await.unwrap(); // FIXME
+                let id = handle.id;
+                (id, handle.wait().await)
             });
         }
         while let Some(res) = join_set.join_next().await {
-            if res.is_err() {
-                return None;
+            match res {
+                Ok((_, TaskStatusKind::Success)) => (),
+                Ok((id, _)) => return Err(BuildError::DependencyFailed(id)),
+                Err(_) => return Err(BuildError::Internal),
             }
         }
     }
-    let _ = nix::build(&drv, sender_log).await.ok()?;
-    Some(())
+    let _slot = SLOTS.acquire(options.priority).await;
+    nix::realise(&drv, &options, sender_log).await?;
+    Ok(responses::BuildOutcome::Built)
 }
 
 async fn abort_thread(
@@ -196,7 +387,7 @@ async fn main_thread(
                     }
                 }
             }
-            Msg::Build(drv, handle_sender) => {
+            Msg::Build(drv, options, handle_sender) => {
                 let (abort_sender, abort_receiver) = oneshot::channel();
                 let (res_sender, res_receiver) = oneshot::channel();
                 let id = if let Some(build) = state.builds.get_mut(&drv) {
@@ -206,22 +397,32 @@ async fn main_thread(
                 } else {
                     let maybe_build: Option<builds::Build> =
                         builds::Build::last(&mut state.conn, &drv)?;
-                    match maybe_build {
+                    // when checking, only a build that was itself checked can
+                    // be reused
+                    let reusable = match &maybe_build {
                         Some(build) => {
-                            if TaskStatusKind::from(&build.task.status()) == TaskStatusKind::Success
+                            TaskStatusKind::from(&build.task.status()) == TaskStatusKind::Success
+                                && (!options.check
+                                    || build.build.outcome()
+                                        == Some(responses::BuildOutcome::Deterministic))
                                 && nix::is_built(&drv).await?
-                            {
-                                let _ = res_sender.send(Some(Some(())));
-                                build.build.id
-                            } else {
-                                state
-                                    .new_build(drv, &sender, abort_receiver, res_sender)
-                                    .await?
-                            }
                         }
-                        None => {
+                        None => false,
+                    };
+                    match maybe_build {
+                        Some(build) if reusable => {
+                            let _ = res_sender.send(TaskStatusKind::Success);
+                            build.build.id
+                        }
+                        _ => {
+                            // there is nothing to compare with until the
+                            // outputs are built once
+                            let options = nix::BuildOptions {
+                                check: options.check && nix::is_built(&drv).await?,
+                                ..options
+                            };
                             state
-                                .new_build(drv, &sender, abort_receiver, res_sender)
+                                .new_build(drv, options, &sender, abort_receiver, res_sender)
                                 .await?
                         }
                     }
@@ -247,7 +448,7 @@ async fn main_thread(
     for (_, build) in state.builds {
         build.build.task.cancel();
         for sender in build.senders {
-            let _ = sender.send(None);
+            let _ = sender.send(TaskStatusKind::Canceled);
         }
     }
     Ok(())
@@ -275,9 +476,13 @@ impl Builder {
         Self { sender, watch }
     }
 
-    pub fn run(&self, drv: DrvPath) -> BuildHandle {
+    /// Builds a derivation with the given options, unset time limits falling
+    /// back to the global defaults
+    pub fn run(&self, drv: DrvPath, options: nix::BuildOptions) -> BuildHandle {
         let (handle_sender, handle_receiver) = oneshot::channel();
-        self.sender.send(Msg::Build(drv, handle_sender)).unwrap(); // FIXME
+        self.sender
+            .send(Msg::Build(drv, options, handle_sender))
+            .unwrap(); // FIXME
         handle_receiver.blocking_recv().unwrap() // FIXME
     }
 
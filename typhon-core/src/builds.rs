@@ -9,6 +9,22 @@ use typhon_types::*;
 
 use diesel::prelude::*;
 
+impl models::Build {
+    pub fn failed_dependency(&self) -> Option<handles::Build> {
+        use std::str::FromStr;
+        use uuid::Uuid;
+        self.failed_dependency
+            .as_ref()
+            .and_then(|uuid| Uuid::from_str(uuid).ok())
+            .map(handles::build)
+    }
+    pub fn outcome(&self) -> Option<responses::BuildOutcome> {
+        self.outcome
+            .as_ref()
+            .and_then(|outcome| serde_json::from_str(outcome).ok())
+    }
+}
+
 #[derive(Clone)]
 pub struct Build {
     pub task: tasks::Task,
@@ -39,6 +55,8 @@ impl Build {
         responses::BuildInfo {
             handle: self.handle(),
             drv: self.build.drv.clone(),
+            failed_dependency: self.build.failed_dependency(),
+            outcome: self.build.outcome(),
             status: self.task.status(),
         }
     }
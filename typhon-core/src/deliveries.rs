@@ -0,0 +1,190 @@
+use crate::actions::webhooks;
+use crate::error::Error;
+use crate::handles;
+use crate::models;
+use crate::projects;
+use crate::responses;
+use crate::schema;
+use crate::Conn;
+use crate::POOL;
+use crate::{log_event, Event};
+
+use typhon_types::data::TaskStatusKind;
+
+use diesel::prelude::*;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct Delivery {
+    pub project: models::Project,
+    pub delivery: models::Delivery,
+}
+
+impl Delivery {
+    pub fn get(conn: &mut Conn, handle: &handles::Delivery) -> Result<Self, Error> {
+        let (delivery, project) = schema::deliveries::table
+            .inner_join(schema::projects::table)
+            .filter(schema::deliveries::uuid.eq(handle.uuid.to_string()))
+            .first(conn)
+            .optional()?
+            .ok_or(Error::DeliveryNotFound(handle.clone()))?;
+        Ok(Self { project, delivery })
+    }
+
+    pub fn handle(&self) -> handles::Delivery {
+        handles::delivery(Uuid::from_str(&self.delivery.uuid).unwrap())
+    }
+
+    pub fn info(&self, conn: &mut Conn) -> Result<responses::DeliveryInfo, Error> {
+        let action_uuid = schema::actions::table
+            .find(self.delivery.action_id)
+            .select(schema::actions::uuid)
+            .first::<String>(conn)?;
+        Ok(responses::DeliveryInfo {
+            handle: self.handle(),
+            action: handles::action(Uuid::from_str(&action_uuid).unwrap()),
+            body: self.delivery.body.clone(),
+            commands: self
+                .delivery
+                .commands
+                .as_ref()
+                .and_then(|commands| serde_json::from_str(commands).ok()),
+            headers: serde_json::from_str(&self.delivery.headers).unwrap_or_default(),
+            outcome: self
+                .delivery
+                .outcome
+                .as_ref()
+                .and_then(|outcome| serde_json::from_str(outcome).ok()),
+            project: handles::project(self.project.name.clone()),
+            time_created: OffsetDateTime::from_unix_timestamp(self.delivery.time_created)?,
+        })
+    }
+
+    /// Records a delivery and runs the `webhook` action on it in the
+    /// background. The commands output by the action are then handled on
+    /// behalf of the project.
+    pub fn new(
+        conn: &mut Conn,
+        project: &projects::Project,
+        input: webhooks::Input,
+    ) -> Result<Self, Error> {
+        let action = project.new_action(
+            conn,
+            &project
+                .project
+                .actions_path
+                .clone()
+                .unwrap_or("/dev/null".to_string()),
+            &"webhook".to_string(),
+            &serde_json::to_value(&input).unwrap(),
+        )?;
+
+        let time_created = OffsetDateTime::now_utc().unix_timestamp();
+        let uuid = Uuid::new_v7(uuid::timestamp::Timestamp::from_unix(
+            uuid::timestamp::context::NoContext,
+            time_created as u64,
+            0,
+        ));
+        let new_delivery = models::NewDelivery {
+            action_id: action.action.id,
+            body: &input.body,
+            headers: &serde_json::to_string(&input.headers).unwrap(),
+            project_id: project.project.id,
+            time_created,
+            uuid: &uuid.to_string(),
+        };
+        let delivery = diesel::insert_into(schema::deliveries::table)
+            .values(&new_delivery)
+            .get_result::<models::Delivery>(conn)?;
+        let delivery = Self {
+            project: project.project.clone(),
+            delivery,
+        };
+
+        let finish = {
+            let self_ = delivery.clone();
+            move |output: Option<String>| {
+                let (commands, outcome) = self_.handle_output(output);
+                let status = match outcome {
+                    responses::DeliveryOutcome::Success => TaskStatusKind::Success,
+                    _ => TaskStatusKind::Failure,
+                };
+                if let Err(e) = self_.finish(commands, outcome) {
+                    tracing::error!("failed to record delivery {}: {}", self_.handle(), e);
+                }
+                status
+            }
+        };
+
+        log_event(Event::DeliveryNew(delivery.handle()));
+
+        action.spawn(conn, finish)?;
+
+        Ok(delivery)
+    }
+
+    pub fn replay(&self, conn: &mut Conn) -> Result<Self, Error> {
+        let input = webhooks::Input {
+            headers: serde_json::from_str(&self.delivery.headers).unwrap_or_default(),
+            body: self.delivery.body.clone(),
+        };
+        let project = projects::Project {
+            refresh_task: None,
+            project: self.project.clone(),
+        };
+        Self::new(conn, &project, input)
+    }
+
+    fn finish(
+        &self,
+        commands: Option<serde_json::Value>,
+        outcome: responses::DeliveryOutcome,
+    ) -> Result<(), Error> {
+        let mut conn = POOL.get().unwrap();
+        diesel::update(&self.delivery)
+            .set((
+                schema::deliveries::commands.eq(commands.map(|commands| commands.to_string())),
+                schema::deliveries::outcome.eq(serde_json::to_string(&outcome).unwrap()),
+            ))
+            .execute(&mut conn)?;
+        log_event(Event::DeliveryFinished(self.handle()));
+        Ok(())
+    }
+
+    fn handle_output(
+        &self,
+        output: Option<String>,
+    ) -> (Option<serde_json::Value>, responses::DeliveryOutcome) {
+        use crate::handle_request_aux;
+        use crate::User;
+        use responses::DeliveryOutcome;
+
+        let Some(output) = output else {
+            return (None, DeliveryOutcome::ActionFailure);
+        };
+        let Ok(commands) = serde_json::from_str::<serde_json::Value>(&output) else {
+            return (None, DeliveryOutcome::InvalidCommands);
+        };
+        let Ok(cmds) = serde_json::from_value::<webhooks::Output>(commands.clone()) else {
+            return (Some(commands), DeliveryOutcome::InvalidCommands);
+        };
+
+        let mut conn = POOL.get().unwrap();
+        let project = handles::project(self.project.name.clone());
+        for cmd in cmds {
+            let req = cmd.lift(project.clone());
+            tracing::trace!("handling request {} from webhook", req);
+            if let Err(e) = handle_request_aux(&mut conn, &User::Admin, &req) {
+                return (
+                    Some(commands),
+                    DeliveryOutcome::CommandFailure(e.to_string()),
+                );
+            }
+        }
+
+        (Some(commands), DeliveryOutcome::Success)
+    }
+}
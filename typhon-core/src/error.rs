@@ -15,28 +15,56 @@ pub enum Error {
     BuildNotFound(handles::Build),
     #[display("Run {_0} was not found")]
     RunNotFound(handles::Run),
+    #[display("Run {_0} has no successful build")]
+    RunNotSuccessful(handles::Run),
     #[display("Bad project declaration")]
     BadProjectDecl,
+    #[display("Webhook delivery {_0} was not found")]
+    DeliveryNotFound(handles::Delivery),
     #[display("Bad jobset declaration: {_0}")]
     BadJobsetDecl(String),
+    #[display("Custom action {_0} was not found")]
+    CustomActionNotFound(String),
     #[display("Evaluation {_0} was not found")]
     EvaluationNotFound(handles::Evaluation),
     #[display("Illegal project handle: {_0}")]
     IllegalProjectHandle(handles::Project),
+    #[display("Invalid action input: {_0}")]
+    InvalidActionInput(String),
+    #[display("Duration of {_0} seconds is out of range")]
+    InvalidDuration(u64),
+    #[display("Keeping {_0} evaluations is out of range")]
+    InvalidKeep(u32),
     #[display("Job {_0} is already running")]
     JobAlreadyRunning(handles::Job),
+    #[display("Job {_1} of jobset {_0} is not failing")]
+    JobNotFailing(handles::Jobset, String),
     #[display("Job {_0} was not found")]
     JobNotFound(handles::Job),
+    #[display("Job {_1} of jobset {_0} is not quarantined")]
+    JobNotQuarantined(handles::Jobset, String),
+    #[display("Jobset {_0} is closed")]
+    JobsetClosed(handles::Jobset),
+    #[display("Jobset {_0} is disabled")]
+    JobsetDisabled(handles::Jobset),
     #[display("Jobset {_0} was not found")]
     JobsetNotFound(handles::Jobset),
     #[display("Log {_0} was not found")]
     LogNotFound(handles::Log),
     #[display("Nix error: {_0}")]
     NixError(nix::Error),
+    #[display("Evaluation {_0} has no previous evaluation")]
+    NoPreviousEvaluation(handles::Evaluation),
+    #[display("No evaluation of jobset {_0} passed")]
+    NoPassingEvaluation(handles::Jobset),
+    #[display("Job {_1} of jobset {_0} never succeeded")]
+    NoSuccessfulJob(handles::Jobset, String),
     #[display("Project {_0} already exists")]
     ProjectAlreadyExists(handles::Project),
     #[display("Project {_0} was not found")]
     ProjectNotFound(handles::Project),
+    #[display("Revisions of jobset {_0} cannot be listed: {_1}")]
+    RevisionsNotListed(handles::Jobset, String),
     #[display("ToDo")]
     Todo,
     #[display("Unexpected database error: {_0}")]
@@ -47,15 +75,6 @@ pub enum Error {
     LoginError,
     #[display("Task error: {_0}")]
     TaskError(task_manager::Error),
-    #[display("{}", display_webhook_failure(_0))]
-    WebhookFailure(Option<String>),
-}
-
-fn display_webhook_failure(output: &Option<String>) -> String {
-    match output {
-        Some(stdout) => format!("Bad webhook output: {stdout}"),
-        None => "Webhook failure".to_string(),
-    }
 }
 
 impl Error {
@@ -113,9 +132,15 @@ impl Into<typhon_types::responses::ResponseError> for Error {
             | Todo => InternalError,
             EvaluationNotFound(_)
             | JobNotFound(_)
+            | JobNotQuarantined(..)
             | JobsetNotFound(_)
+            | NoPassingEvaluation(_)
+            | NoPreviousEvaluation(_)
+            | NoSuccessfulJob(..)
             | ProjectNotFound(_)
             | ActionNotFound(_)
+            | CustomActionNotFound(_)
+            | DeliveryNotFound(_)
             | BuildNotFound(_)
             | RunNotFound(_)
             | LogNotFound(_) => ResourceNotFound(format!("{}", self)),
@@ -124,11 +149,18 @@ impl Into<typhon_types::responses::ResponseError> for Error {
             | BadProjectDecl
             | BadJobsetDecl(_)
             | IllegalProjectHandle(_)
+            | InvalidActionInput(_)
+            | InvalidDuration(_)
+            | InvalidKeep(_)
             | JobAlreadyRunning(_)
+            | JobNotFailing(..)
+            | RunNotSuccessful(_)
+            | JobsetClosed(_)
+            | JobsetDisabled(_)
             | NixError(_)
             | ProjectAlreadyExists(_)
-            | LoginError
-            | WebhookFailure(_) => BadRequest(format!("{}", self)),
+            | RevisionsNotListed(..)
+            | LoginError => BadRequest(format!("{}", self)),
         }
     }
 }
@@ -1,14 +1,18 @@
 use crate::error::Error;
+use crate::forges;
 use crate::jobs;
+use crate::log_event;
 use crate::models;
 use crate::nix;
 use crate::responses;
 use crate::schema;
 use crate::tasks;
 use crate::Conn;
+use crate::EVENT_LOGGER;
 use crate::POOL;
+use crate::RUNTIME;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use typhon_types::data::TaskStatusKind;
 use typhon_types::*;
 
@@ -38,6 +42,9 @@ impl responses::RunInfo {
         let to_action_info =
             |(action, task): (models::Action, models::Task)| responses::ActionInfo {
                 handle: handles::action(Uuid::from_str(&action.uuid).unwrap()),
+                exit_code: action.exit_code,
+                failure: action.failure(),
+                results: action.results(),
                 input: action.input,
                 name: action.name,
                 path: action.path,
@@ -52,10 +59,13 @@ impl responses::RunInfo {
             begin: begin.map(to_action_info),
             build: build.map(|(build, task)| responses::BuildInfo {
                 handle: handles::build(Uuid::from_str(&build.uuid).unwrap()),
+                outcome: build.outcome(),
+                failed_dependency: build.failed_dependency(),
                 drv: build.drv,
                 status: task.status(),
             }),
             end: end.map(to_action_info),
+            reruns: Vec::new(),
         }
     }
 }
@@ -83,6 +93,9 @@ impl responses::JobInfo {
             out: job.out,
             last_run: responses::RunInfo::new(project_handle, &job_handle, run, begin, build, end),
             run_count: job.tries as u32,
+            flaky: job.flaky,
+            required: job.required,
+            quarantine: None,
         }
     }
 }
@@ -123,6 +136,39 @@ impl Evaluation {
         handles::evaluation(Uuid::from_str(&self.evaluation.uuid).unwrap())
     }
 
+    /// Records the author and subject of the evaluated commit in the
+    /// background, when the project has a forge
+    pub fn fetch_commit(&self) {
+        let (Some(forge), Some(revision)) = (
+            forges::forge(&self.project),
+            forges::revision(&self.evaluation.url),
+        ) else {
+            return;
+        };
+        let (id, revision) = (self.evaluation.id, revision.to_string());
+        RUNTIME.spawn(async move {
+            let commit = match forges::commit(&reqwest::Client::new(), &forge, &revision).await {
+                Ok(commit) => commit,
+                Err(e) => {
+                    tracing::warn!("failed to fetch commit {}: {}", revision, e);
+                    return;
+                }
+            };
+            let res = tokio::task::spawn_blocking(move || {
+                diesel::update(schema::evaluations::table.find(id))
+                    .set((
+                        schema::evaluations::commit_author.eq(commit.author),
+                        schema::evaluations::commit_subject.eq(commit.subject),
+                    ))
+                    .execute(&mut POOL.get().unwrap())
+            })
+            .await;
+            if let Ok(Err(e)) = res {
+                tracing::error!("failed to record commit {}: {}", revision, e);
+            }
+        });
+    }
+
     /// Fetch all jobs attached to self
     pub fn jobs(
         project_handle: &handles::Project,
@@ -131,6 +177,41 @@ impl Evaluation {
         filter_name: Option<String>,
         conn: &mut Conn,
     ) -> Result<HashMap<String, responses::JobInfo>, Error> {
+        let (project_id, jobset_name) = schema::evaluations::table
+            .find(eval_id)
+            .select((
+                schema::evaluations::project_id,
+                schema::evaluations::jobset_name,
+            ))
+            .first::<(i32, String)>(conn)?;
+        let mut quarantines = crate::jobsets::active_quarantines(conn, project_id, &jobset_name)?;
+        Ok(Self::jobs_of(
+            project_handle,
+            &HashMap::from([(eval_id, eval_handle.clone())]),
+            filter_name,
+            conn,
+        )?
+        .into_iter()
+        .map(|(_, info)| {
+            let name = info.handle.name.clone();
+            let info = responses::JobInfo {
+                quarantine: quarantines.remove(&name),
+                ..info
+            };
+            (name, info)
+        })
+        .collect())
+    }
+
+    /// The jobs of several evaluations of a project with their last run, in a
+    /// single query, along with the ids of their evaluations. Quarantines are
+    /// not looked up.
+    pub fn jobs_of(
+        project_handle: &handles::Project,
+        evaluations: &HashMap<i32, handles::Evaluation>,
+        filter_name: Option<String>,
+        conn: &mut Conn,
+    ) -> Result<Vec<(i32, responses::JobInfo)>, Error> {
         let (begin_action, end_action, begin_task, build_task, end_task, subruns) = diesel::alias!(
             schema::actions as begin_action,
             schema::actions as end_action,
@@ -169,7 +250,7 @@ impl Evaluation {
                     .select(diesel::dsl::max(subruns.field(schema::runs::id)))
                     .single_value()),
             )
-            .filter(schema::jobs::evaluation_id.eq(eval_id))
+            .filter(schema::jobs::evaluation_id.eq_any(evaluations.keys().copied()))
             .into_boxed();
         if let Some(name) = filter_name {
             query = query.filter(schema::jobs::name.eq(name));
@@ -198,29 +279,107 @@ impl Evaluation {
             .into_iter()
             .map(
                 |(job, run, begin, build, end): (models::Job, models::Run, _, _, _)| {
-                    let name = job.name.clone();
-                    (
-                        name,
-                        responses::JobInfo::new(
-                            project_handle,
-                            &eval_handle,
-                            job,
-                            run,
-                            begin,
-                            build,
-                            end,
-                        ),
-                    )
+                    let eval_id = job.evaluation_id;
+                    let info = responses::JobInfo::new(
+                        project_handle,
+                        &evaluations[&eval_id],
+                        job,
+                        run,
+                        begin,
+                        build,
+                        end,
+                    );
+                    (eval_id, info)
                 },
             )
             .collect())
     }
 
+    /// Compares the jobs of the evaluation with those of `other`, or of the
+    /// previous evaluation of the jobset
+    pub fn compare(
+        &self,
+        conn: &mut Conn,
+        other: Option<&handles::Evaluation>,
+    ) -> Result<responses::EvaluationComparison, Error> {
+        let other = match other {
+            Some(handle) => Self::get(conn, handle)?,
+            None => {
+                let uuid = schema::evaluations::table
+                    .filter(schema::evaluations::project_id.eq(self.project.id))
+                    .filter(schema::evaluations::jobset_name.eq(&self.evaluation.jobset_name))
+                    .filter(schema::evaluations::time_created.lt(self.evaluation.time_created))
+                    .filter(schema::evaluations::bisection.eq(false))
+                    .order(schema::evaluations::time_created.desc())
+                    .select(schema::evaluations::uuid)
+                    .first::<String>(conn)
+                    .optional()?
+                    .ok_or(Error::NoPreviousEvaluation(self.handle()))?;
+                Self::get(conn, &handles::evaluation(Uuid::from_str(&uuid).unwrap()))?
+            }
+        };
+        let jobs = self.info(conn)?.jobs;
+        let other_jobs = other.info(conn)?.jobs;
+        let kind = |job: &responses::JobInfo| TaskStatusKind::from(&job.counted_status());
+        let failed = |kind: TaskStatusKind| kind.is_failure() || kind == TaskStatusKind::Canceled;
+        let sorted = |mut names: Vec<String>| {
+            names.sort();
+            names
+        };
+        let mut comparison = responses::EvaluationComparison {
+            other: other.handle(),
+            added: sorted(
+                jobs.keys()
+                    .filter(|name| !other_jobs.contains_key(*name))
+                    .cloned()
+                    .collect(),
+            ),
+            removed: sorted(
+                other_jobs
+                    .keys()
+                    .filter(|name| !jobs.contains_key(*name))
+                    .cloned()
+                    .collect(),
+            ),
+            changed: Vec::new(),
+            newly_failing: Vec::new(),
+            fixed: Vec::new(),
+        };
+        for (name, job) in jobs.iter() {
+            let Some(other_job) = other_jobs.get(name) else {
+                continue;
+            };
+            if job.drv != other_job.drv {
+                comparison.changed.push(name.clone());
+            }
+            match (kind(job), kind(other_job)) {
+                (kind, other_kind) if failed(kind) && !failed(other_kind) => {
+                    comparison.newly_failing.push(name.clone())
+                }
+                (TaskStatusKind::Success, other_kind) if failed(other_kind) => {
+                    comparison.fixed.push(name.clone())
+                }
+                _ => (),
+            }
+        }
+        comparison.changed.sort();
+        comparison.newly_failing.sort();
+        comparison.fixed.sort();
+        Ok(comparison)
+    }
+
     pub fn info(&self, conn: &mut Conn) -> Result<responses::EvaluationInfo, Error> {
         Ok(responses::EvaluationInfo {
             handle: self.handle(),
             actions_path: self.evaluation.actions_path.clone(),
+            commit: self
+                .evaluation
+                .commit_author
+                .clone()
+                .zip(self.evaluation.commit_subject.clone())
+                .map(|(author, subject)| responses::Commit { author, subject }),
             flake: self.evaluation.flake,
+            gate: self.evaluation.gate(),
             jobs: if self.task.status_kind() == TaskStatusKind::Success {
                 Self::jobs(
                     &handles::project(self.project.name.clone()),
@@ -232,7 +391,14 @@ impl Evaluation {
             } else {
                 HashMap::new()
             },
+            job_counts: self.evaluation.job_counts(),
             jobset_name: self.evaluation.jobset_name.clone(),
+            lock: self
+                .evaluation
+                .flake_lock
+                .as_ref()
+                .and_then(|lock| serde_json::from_str(lock).ok()),
+            outcome: self.evaluation.outcome(),
             project: handles::project(self.project.name.clone()),
             status: self.task.status(),
             time_created: time::OffsetDateTime::from_unix_timestamp(self.evaluation.time_created)?,
@@ -240,6 +406,239 @@ impl Evaluation {
         })
     }
 
+    /// Computes the aggregate outcome of the jobs of the evaluation and
+    /// stores it, along with the number of jobs with each status and the
+    /// outcome of the required jobs. Logs an event when the outcome settles.
+    pub fn update_outcome(&self, conn: &mut Conn) -> Result<(), Error> {
+        use typhon_types::data::EvaluationOutcome;
+
+        let aggregate = |eval_status: TaskStatusKind, kinds: &[TaskStatusKind]| {
+            let failed =
+                |kind: &TaskStatusKind| kind.is_failure() || *kind == TaskStatusKind::Canceled;
+            match eval_status {
+                TaskStatusKind::Pending => EvaluationOutcome::Pending,
+                TaskStatusKind::Success if kinds.contains(&TaskStatusKind::Pending) => {
+                    EvaluationOutcome::Pending
+                }
+                TaskStatusKind::Success if kinds.iter().any(failed) => EvaluationOutcome::Fail,
+                TaskStatusKind::Success => EvaluationOutcome::Pass,
+                _ => EvaluationOutcome::Fail,
+            }
+        };
+        let eval_status = self.task.status_kind();
+        let jobs = match eval_status {
+            TaskStatusKind::Success => Self::jobs(
+                &handles::project(self.project.name.clone()),
+                &self.handle(),
+                self.evaluation.id,
+                None,
+                conn,
+            )?,
+            _ => HashMap::new(),
+        };
+        let kinds: Vec<TaskStatusKind> = jobs
+            .values()
+            .map(|job| TaskStatusKind::from(&job.counted_status()))
+            .collect();
+        let mut job_counts: HashMap<TaskStatusKind, u32> = HashMap::new();
+        for kind in kinds.iter() {
+            *job_counts.entry(*kind).or_default() += 1;
+        }
+        let outcome = aggregate(eval_status, &kinds);
+        let gate = if jobs.values().any(|job| job.required) {
+            let required: Vec<TaskStatusKind> = jobs
+                .values()
+                .filter(|job| job.required)
+                .map(|job| TaskStatusKind::from(&job.counted_status()))
+                .collect();
+            aggregate(eval_status, &required)
+        } else {
+            outcome
+        };
+        diesel::update(&self.evaluation)
+            .set((
+                schema::evaluations::gate.eq(serde_json::to_string(&gate).unwrap()),
+                schema::evaluations::job_counts.eq(serde_json::to_string(&job_counts).unwrap()),
+                schema::evaluations::outcome.eq(serde_json::to_string(&outcome).unwrap()),
+            ))
+            .execute(conn)?;
+        self.update_latest_jobs(conn, &jobs)?;
+        let previous = self.evaluation.outcome();
+        if previous != outcome && outcome != EvaluationOutcome::Pending {
+            log_event(Event::EvaluationSettled(self.handle()));
+            log_event(Event::JobsUpdated(handles::jobset((
+                self.project.name.clone(),
+                self.evaluation.jobset_name.clone(),
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Makes the successful jobs of the evaluation the latest successful
+    /// instances of their names in the jobset, unless they succeeded in a newer
+    /// evaluation or the evaluation is of a past revision, for a bisection.
+    /// Latest instances that were rerun without success are forgotten, until
+    /// another instance succeeds.
+    fn update_latest_jobs(
+        &self,
+        conn: &mut Conn,
+        jobs: &HashMap<String, responses::JobInfo>,
+    ) -> Result<(), Error> {
+        if self.evaluation.bisection {
+            return Ok(());
+        }
+        let Some(jobset_id) = schema::jobsets::table
+            .filter(schema::jobsets::project_id.eq(self.project.id))
+            .filter(schema::jobsets::name.eq(&self.evaluation.jobset_name))
+            .select(schema::jobsets::id)
+            .first::<i32>(conn)
+            .optional()?
+        else {
+            return Ok(());
+        };
+        let current: HashMap<String, (i32, i64)> = schema::latest_jobs::table
+            .inner_join(schema::jobs::table.inner_join(schema::evaluations::table))
+            .filter(schema::latest_jobs::jobset_id.eq(jobset_id))
+            .select((
+                schema::latest_jobs::job_name,
+                schema::latest_jobs::job_id,
+                schema::evaluations::time_created,
+            ))
+            .load::<(String, i32, i64)>(conn)?
+            .into_iter()
+            .map(|(name, job_id, time_created)| (name, (job_id, time_created)))
+            .collect();
+        let ids: HashMap<String, i32> = schema::jobs::table
+            .filter(schema::jobs::evaluation_id.eq(self.evaluation.id))
+            .select((schema::jobs::name, schema::jobs::id))
+            .load::<(String, i32)>(conn)?
+            .into_iter()
+            .collect();
+        for (name, job) in jobs {
+            let status = TaskStatusKind::from(&responses::TaskStatus::from(job));
+            let Some(job_id) = ids.get(name) else {
+                continue;
+            };
+            let is_latest = current.get(name).is_some_and(|(id, _)| id == job_id);
+            if status != TaskStatusKind::Success {
+                if is_latest {
+                    diesel::delete(
+                        schema::latest_jobs::table
+                            .filter(schema::latest_jobs::jobset_id.eq(jobset_id))
+                            .filter(schema::latest_jobs::job_name.eq(name)),
+                    )
+                    .execute(conn)?;
+                }
+                continue;
+            }
+            let newer = current
+                .get(name)
+                .is_some_and(|(_, time_created)| *time_created > self.evaluation.time_created);
+            if is_latest || newer {
+                continue;
+            }
+            let latest = models::NewLatestJob {
+                job_id: *job_id,
+                job_name: name,
+                jobset_id,
+            };
+            diesel::insert_into(schema::latest_jobs::table)
+                .values(&latest)
+                .on_conflict((
+                    schema::latest_jobs::jobset_id,
+                    schema::latest_jobs::job_name,
+                ))
+                .do_update()
+                .set(&latest)
+                .execute(conn)?;
+        }
+        Ok(())
+    }
+
+    /// Reruns the failed jobs selected by `filter`, returns how many were
+    /// started. The new runs are all created before any of them starts.
+    pub fn rerun_failed(
+        &self,
+        conn: &mut Conn,
+        filter: &requests::RerunFilter,
+    ) -> Result<u32, Error> {
+        use typhon_types::data::TaskStatusKind;
+        use typhon_types::requests::RunStage;
+        use typhon_types::responses::TaskStatus;
+
+        let failed = |status: Option<TaskStatus>| {
+            status
+                .map(TaskStatusKind::from)
+                .is_some_and(|kind| kind.is_failure() || kind == TaskStatusKind::Canceled)
+        };
+        let infos = Self::jobs(
+            &handles::project(self.project.name.clone()),
+            &self.handle(),
+            self.evaluation.id,
+            None,
+            conn,
+        )?;
+        let selected: Vec<jobs::Job> = schema::jobs::table
+            .filter(schema::jobs::evaluation_id.eq(self.evaluation.id))
+            .load::<models::Job>(conn)?
+            .into_iter()
+            .filter(|job| {
+                filter
+                    .name
+                    .as_ref()
+                    .is_none_or(|pattern| matches_pattern(pattern, &job.name))
+            })
+            .filter(|job| {
+                let Some(info) = infos.get(&job.name) else {
+                    return false;
+                };
+                let run = &info.last_run;
+                [
+                    (
+                        RunStage::Begin,
+                        run.begin.as_ref().map(|action| action.status),
+                    ),
+                    (
+                        RunStage::Build,
+                        run.build.as_ref().map(|build| build.status),
+                    ),
+                    (RunStage::End, run.end.as_ref().map(|action| action.status)),
+                ]
+                .into_iter()
+                .any(|(stage, status)| filter.stage.is_none_or(|s| s == stage) && failed(status))
+            })
+            .map(|job| jobs::Job {
+                job,
+                evaluation: self.evaluation.clone(),
+                project: self.project.clone(),
+            })
+            .collect();
+
+        let runs = conn.transaction::<Vec<crate::runs::Run>, Error, _>(|conn| {
+            selected.iter().map(|job| job.new_run(conn)).collect()
+        })?;
+        let mut started = 0;
+        let mut errors = Vec::new();
+        for run in runs.iter() {
+            run.log_new();
+            match run.run(conn) {
+                Ok(()) => started += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+        if let Some(e) = errors.first() {
+            tracing::error!(
+                "failed to start {} of {} reruns in evaluation {}: {}",
+                errors.len(),
+                runs.len(),
+                self.handle(),
+                e
+            );
+        }
+
+        Ok(started)
+    }
+
     pub async fn run(
         self,
         sender: mpsc::UnboundedSender<String>,
@@ -259,14 +658,24 @@ impl Evaluation {
     }
 
     fn create_new_jobs(&self, conn: &mut Conn, new_jobs: nix::NewJobs) -> Result<(), Error> {
+        let required_jobs = schema::jobsets::table
+            .filter(schema::jobsets::project_id.eq(self.project.id))
+            .filter(schema::jobsets::name.eq(&self.evaluation.jobset_name))
+            .select(schema::jobsets::required_jobs)
+            .first::<Option<String>>(conn)
+            .optional()?
+            .flatten();
         let created_runs = conn.transaction::<Vec<crate::runs::Run>, Error, _>(|conn| {
             let created_jobs: Vec<crate::jobs::Job> = new_jobs
                 .into_iter()
-                .map(|(name, (drv, dist))| {
+                .map(|(name, new_job)| {
+                    let drv = new_job.drv;
                     let new_job = models::NewJob {
-                        dist,
+                        dist: new_job.dist,
                         drv: &drv.path.to_string(),
                         evaluation_id: self.evaluation.id,
+                        max_attempts: new_job.max_attempts.map(|n| n as i32),
+                        max_silent_time: new_job.max_silent_time.map(|time| time as i64),
                         name: &name,
                         out: drv
                             .outputs
@@ -274,6 +683,11 @@ impl Evaluation {
                             .last()
                             .expect("TODO: derivations can have multiple outputs")
                             .1,
+                        required: new_job.required
+                            || required_jobs
+                                .as_ref()
+                                .is_some_and(|pattern| matches_pattern(pattern, &name)),
+                        timeout: new_job.timeout.map(|timeout| timeout as i64),
                         tries: 0,
                     };
                     let job = diesel::insert_into(schema::jobs::table)
@@ -293,9 +707,155 @@ impl Evaluation {
         })?;
 
         for run in created_runs {
+            run.log_new();
             run.run(conn)?;
         }
 
         Ok(())
     }
 }
+
+/// Whether `name` matches `pattern`, where `*` matches any sequence of
+/// characters
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(i) => rest = &rest[i + part.len()..],
+                    None => return false,
+                }
+            }
+            rest.ends_with(last)
+        }
+    }
+}
+
+impl models::Evaluation {
+    pub fn job_counts(&self) -> HashMap<TaskStatusKind, u32> {
+        serde_json::from_str(&self.job_counts).unwrap_or_default()
+    }
+
+    pub fn gate(&self) -> data::EvaluationOutcome {
+        serde_json::from_str(&self.gate).unwrap_or_default()
+    }
+
+    pub fn outcome(&self) -> data::EvaluationOutcome {
+        serde_json::from_str(&self.outcome).unwrap_or_default()
+    }
+}
+
+/// Updates the outcome of an evaluation
+fn update_outcome(conn: &mut Conn, handle: &handles::Evaluation) -> Result<(), Error> {
+    Evaluation::get(conn, handle)?.update_outcome(conn)
+}
+
+/// Updates the outcome of all the evaluations of a jobset with a given job,
+/// in the background, e.g. when the job is quarantined
+pub fn update_outcomes(project_id: i32, jobset_name: String, job_name: String) {
+    RUNTIME.spawn(async move {
+        let res = tokio::task::spawn_blocking(move || {
+            let mut conn = POOL.get().unwrap();
+            let uuids = schema::evaluations::table
+                .inner_join(schema::jobs::table)
+                .filter(schema::evaluations::project_id.eq(project_id))
+                .filter(schema::evaluations::jobset_name.eq(&jobset_name))
+                .filter(schema::jobs::name.eq(&job_name))
+                .select(schema::evaluations::uuid)
+                .load::<String>(&mut conn)?;
+            for uuid in uuids {
+                update_outcome(
+                    &mut conn,
+                    &handles::evaluation(Uuid::from_str(&uuid).unwrap()),
+                )?;
+            }
+            Ok::<_, Error>(())
+        })
+        .await;
+        if let Ok(Err(e)) = res {
+            tracing::error!("failed to update the outcomes of evaluations: {}", e);
+        }
+    });
+}
+
+/// How long events are gathered, in milliseconds, before the outcomes of the
+/// evaluations they concern are updated
+const OUTCOME_DEBOUNCE: u64 = 500;
+
+/// Updates the outcome of evaluations as their runs progress. Evaluations
+/// whose outcome is still pending are updated first, in case events were
+/// missed. Events are gathered for a while so that an evaluation whose runs
+/// progress together is only updated once.
+pub fn listen() {
+    use futures_core::stream::Stream;
+
+    let Some(events) = EVENT_LOGGER.listen() else {
+        return;
+    };
+    RUNTIME.spawn(async move {
+        let res = tokio::task::spawn_blocking(|| {
+            let mut conn = POOL.get().unwrap();
+            let uuids = schema::evaluations::table
+                .filter(
+                    schema::evaluations::outcome
+                        .eq(serde_json::to_string(&data::EvaluationOutcome::Pending).unwrap()),
+                )
+                .select(schema::evaluations::uuid)
+                .load::<String>(&mut conn)?;
+            for uuid in uuids {
+                update_outcome(
+                    &mut conn,
+                    &handles::evaluation(Uuid::from_str(&uuid).unwrap()),
+                )?;
+            }
+            Ok::<_, Error>(())
+        })
+        .await;
+        if let Ok(Err(e)) = res {
+            tracing::error!(
+                "failed to update the outcomes of pending evaluations: {}",
+                e
+            );
+        }
+        let evaluation = |event| match event {
+            Event::RunUpdated(handle) => Some(handle.job.evaluation),
+            Event::EvaluationFinished(handle) => Some(handle),
+            _ => None,
+        };
+        let mut events = std::pin::pin!(events);
+        while let Some(event) = std::future::poll_fn(|cx| events.as_mut().poll_next(cx)).await {
+            let Some(handle) = evaluation(event) else {
+                continue;
+            };
+            let mut handles = HashSet::from([handle]);
+            let deadline =
+                tokio::time::Instant::now() + std::time::Duration::from_millis(OUTCOME_DEBOUNCE);
+            while let Ok(Some(event)) = tokio::time::timeout_at(
+                deadline,
+                std::future::poll_fn(|cx| events.as_mut().poll_next(cx)),
+            )
+            .await
+            {
+                handles.extend(evaluation(event));
+            }
+            let res = tokio::task::spawn_blocking(move || {
+                let mut conn = POOL.get().unwrap();
+                for handle in handles {
+                    if let Err(e) = update_outcome(&mut conn, &handle) {
+                        tracing::error!("failed to update the outcome of an evaluation: {}", e);
+                    }
+                }
+            })
+            .await;
+            if let Err(e) = res {
+                tracing::error!("evaluation outcome update task panicked: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,617 @@
+use crate::error;
+use crate::evaluations;
+use crate::models;
+use crate::projects;
+use crate::responses;
+use crate::runs;
+use crate::Conn;
+use crate::Settings;
+use crate::EVENT_LOGGER;
+use crate::POOL;
+use crate::RUNTIME;
+
+use typhon_types::data::TaskStatusKind;
+use typhon_types::requests::{ForgeKind, ForgeSettings};
+use typhon_types::Event;
+
+use futures_core::stream::Stream;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
+use serde_json::json;
+
+#[derive(Debug, derive_more::Display)]
+pub enum Error {
+    #[display("HTTP error: {_0}")]
+    Http(reqwest::Error),
+    #[display("Forge answered {_0}: {_1}")]
+    Rejected(reqwest::StatusCode, String),
+    #[display("Forge listed only {_0} of {_1} commits")]
+    Truncated(usize, u64),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
+
+/// A commit status, as understood by all supported forges
+#[derive(Clone, Debug)]
+pub struct Status {
+    pub context: String,
+    pub state: TaskStatusKind,
+    pub target_url: Option<String>,
+}
+
+/// The revision of a locked flake URL, e.g. `github:owner/repo/<rev>` or
+/// `git+https://example.com/repo?ref=main&rev=<rev>`
+pub fn revision(url: &str) -> Option<&str> {
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (url, None),
+    };
+    let rev = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|param| param.strip_prefix("rev="))
+        .or_else(|| match path.split_once(':') {
+            Some(("github" | "gitlab" | "sourcehut", path)) => path.split('/').nth(2),
+            _ => None,
+        })?;
+    (rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())).then_some(rev)
+}
+
+/// A locked flake URL moved to another revision, without the attributes that
+/// pinned its contents so that it can be locked again
+pub fn at_revision(url: &str, revision: &str) -> Option<String> {
+    let url = url.replace(self::revision(url)?, revision);
+    Some(match url.split_once('?') {
+        Some((path, query)) => {
+            let query: Vec<&str> = query
+                .split('&')
+                .filter(|param| {
+                    !["narHash=", "lastModified=", "revCount="]
+                        .iter()
+                        .any(|attr| param.starts_with(attr))
+                })
+                .collect();
+            match query.is_empty() {
+                true => path.to_string(),
+                false => format!("{path}?{}", query.join("&")),
+            }
+        }
+        None => url,
+    })
+}
+
+/// The commits after `base` up to `head`, the oldest first. Fails if the
+/// forge does not list all of them, as GitHub does past 250 commits.
+pub async fn revisions(
+    client: &reqwest::Client,
+    forge: &ForgeSettings,
+    base: &str,
+    head: &str,
+) -> Result<Vec<String>, Error> {
+    let api = forge.api.trim_end_matches('/');
+    let (request, key) = match forge.kind {
+        ForgeKind::GitHub | ForgeKind::Gitea => {
+            let keyword = match forge.kind {
+                ForgeKind::GitHub => "Bearer",
+                _ => "token",
+            };
+            let request = client
+                .get(format!(
+                    "{api}/repos/{}/compare/{base}...{head}",
+                    forge.repository
+                ))
+                .header(AUTHORIZATION, format!("{keyword} {}", forge.token));
+            (request, "sha")
+        }
+        ForgeKind::GitLab => {
+            let request = client
+                .get(format!(
+                    "{api}/projects/{}/repository/compare",
+                    urlencoding::encode(&forge.repository)
+                ))
+                .query(&[("from", base), ("to", head)])
+                .header("PRIVATE-TOKEN", &forge.token);
+            (request, "id")
+        }
+    };
+    let response = request.header(USER_AGENT, "typhon").send().await?;
+    if !response.status().is_success() {
+        let code = response.status();
+        return Err(Error::Rejected(
+            code,
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+    let body: serde_json::Value = response.json().await?;
+    let revisions: Vec<String> = body["commits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|commit| commit[key].as_str())
+        .map(String::from)
+        .collect();
+    match body["total_commits"].as_u64() {
+        Some(total) if total > revisions.len() as u64 => {
+            Err(Error::Truncated(revisions.len(), total))
+        }
+        _ => Ok(revisions),
+    }
+}
+
+/// The author and subject of a commit
+pub async fn commit(
+    client: &reqwest::Client,
+    forge: &ForgeSettings,
+    revision: &str,
+) -> Result<responses::Commit, Error> {
+    let api = forge.api.trim_end_matches('/');
+    let request = match forge.kind {
+        ForgeKind::GitHub => client
+            .get(format!(
+                "{api}/repos/{}/commits/{revision}",
+                forge.repository
+            ))
+            .header(AUTHORIZATION, format!("Bearer {}", forge.token)),
+        ForgeKind::Gitea => client
+            .get(format!(
+                "{api}/repos/{}/git/commits/{revision}",
+                forge.repository
+            ))
+            .header(AUTHORIZATION, format!("token {}", forge.token)),
+        ForgeKind::GitLab => client
+            .get(format!(
+                "{api}/projects/{}/repository/commits/{revision}",
+                urlencoding::encode(&forge.repository)
+            ))
+            .header("PRIVATE-TOKEN", &forge.token),
+    };
+    let response = request.header(USER_AGENT, "typhon").send().await?;
+    if !response.status().is_success() {
+        let code = response.status();
+        return Err(Error::Rejected(
+            code,
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+    let body: serde_json::Value = response.json().await?;
+    let (author, message) = match forge.kind {
+        ForgeKind::GitLab => (&body["author_name"], &body["message"]),
+        _ => (
+            &body["commit"]["author"]["name"],
+            &body["commit"]["message"],
+        ),
+    };
+    Ok(responses::Commit {
+        author: author.as_str().unwrap_or_default().to_string(),
+        subject: message
+            .as_str()
+            .and_then(|message| message.lines().next())
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+fn request(
+    client: &reqwest::Client,
+    forge: &ForgeSettings,
+    revision: &str,
+    status: &Status,
+) -> reqwest::RequestBuilder {
+    let api = forge.api.trim_end_matches('/');
+    let request = match forge.kind {
+        ForgeKind::GitHub | ForgeKind::Gitea => {
+            let state = match status.state {
+                TaskStatusKind::Pending => "pending",
+                TaskStatusKind::Success | TaskStatusKind::Skipped => "success",
+                TaskStatusKind::Failure
+                | TaskStatusKind::TimedOut
+                | TaskStatusKind::DependencyFailed => "failure",
+                TaskStatusKind::Canceled | TaskStatusKind::InfrastructureError => "error",
+            };
+            let keyword = match forge.kind {
+                ForgeKind::GitHub => "Bearer",
+                _ => "token",
+            };
+            client
+                .post(format!(
+                    "{api}/repos/{}/statuses/{revision}",
+                    forge.repository
+                ))
+                .header(AUTHORIZATION, format!("{keyword} {}", forge.token))
+                .json(&json!({
+                    "context": status.context,
+                    "state": state,
+                    "target_url": status.target_url,
+                }))
+        }
+        ForgeKind::GitLab => {
+            let state = match status.state {
+                TaskStatusKind::Pending => "running",
+                TaskStatusKind::Success => "success",
+                TaskStatusKind::Skipped => "skipped",
+                TaskStatusKind::Failure
+                | TaskStatusKind::TimedOut
+                | TaskStatusKind::DependencyFailed
+                | TaskStatusKind::InfrastructureError => "failed",
+                TaskStatusKind::Canceled => "canceled",
+            };
+            client
+                .post(format!(
+                    "{api}/projects/{}/statuses/{revision}",
+                    urlencoding::encode(&forge.repository)
+                ))
+                .header("PRIVATE-TOKEN", &forge.token)
+                .json(&json!({
+                    "name": status.context,
+                    "state": state,
+                    "target_url": status.target_url,
+                }))
+        }
+    };
+    // GitHub rejects requests without a user agent
+    request.header(USER_AGENT, "typhon")
+}
+
+/// Sets the status of a commit on a forge
+pub async fn report(
+    client: &reqwest::Client,
+    forge: &ForgeSettings,
+    revision: &str,
+    status: &Status,
+) -> Result<(), Error> {
+    let response = request(client, forge, revision, status).send().await?;
+    if !response.status().is_success() {
+        let code = response.status();
+        return Err(Error::Rejected(
+            code,
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn forge(project: &models::Project) -> Option<ForgeSettings> {
+    projects::Project {
+        refresh_task: None,
+        project: project.clone(),
+    }
+    .forge()
+}
+
+fn target_url(evaluation: &models::Evaluation, job: Option<&str>) -> Option<String> {
+    let url = Settings::get().url.as_ref()?.trim_end_matches('/');
+    Some(match job {
+        Some(job) => format!(
+            "{url}/evaluation/{}/{}",
+            evaluation.uuid,
+            urlencoding::encode(job)
+        ),
+        None => format!("{url}/evaluation/{}", evaluation.uuid),
+    })
+}
+
+/// The status to report for an event, along with the forge and the revision
+/// it applies to
+fn status(
+    conn: &mut Conn,
+    event: &Event,
+) -> Result<Option<(ForgeSettings, String, Status)>, error::Error> {
+    let (project, evaluation, status) = match event {
+        Event::RunUpdated(handle) => {
+            let run = runs::Run::get(conn, handle)?;
+            let state = TaskStatusKind::from(&responses::TaskStatus::from(&run.info()));
+            // the failures of a quarantined job are not reported
+            let state = if state.is_failure() && run.is_quarantined(conn)? {
+                TaskStatusKind::Skipped
+            } else {
+                state
+            };
+            let status = Status {
+                context: format!("Typhon: {}", run.job.name),
+                state,
+                target_url: target_url(&run.evaluation, Some(&run.job.name)),
+            };
+            (run.project, run.evaluation, status)
+        }
+        Event::EvaluationFinished(handle) => {
+            let evaluation = evaluations::Evaluation::get(conn, handle)?;
+            let status = Status {
+                context: "Typhon".to_string(),
+                state: evaluation.task.status_kind(),
+                target_url: target_url(&evaluation.evaluation, None),
+            };
+            (evaluation.project, evaluation.evaluation, status)
+        }
+        _ => return Ok(None),
+    };
+    // evaluations of past revisions, made for bisections, are not reported
+    if evaluation.bisection {
+        return Ok(None);
+    }
+    let Some(forge) = forge(&project) else {
+        return Ok(None);
+    };
+    let Some(revision) = revision(&evaluation.url) else {
+        tracing::debug!(
+            "no revision in {}, not reporting to {}",
+            evaluation.url,
+            forge.kind
+        );
+        return Ok(None);
+    };
+    Ok(Some((forge, revision.to_string(), status)))
+}
+
+/// Reports the statuses of runs and evaluations to the forges of their
+/// projects. Statuses are reported one at a time, in the order of events.
+pub fn listen() {
+    let Some(events) = EVENT_LOGGER.listen() else {
+        return;
+    };
+    RUNTIME.spawn(async move {
+        let client = reqwest::Client::new();
+        let mut events = std::pin::pin!(events);
+        while let Some(event) = std::future::poll_fn(|cx| events.as_mut().poll_next(cx)).await {
+            let res = tokio::task::spawn_blocking(move || {
+                let mut conn = POOL.get().unwrap();
+                status(&mut conn, &event)
+            })
+            .await;
+            match res {
+                Ok(Ok(Some((forge, revision, status)))) => {
+                    if let Err(e) = report(&client, &forge, &revision, &status).await {
+                        tracing::warn!(
+                            "failed to report status of {} on {} to {}: {}",
+                            status.context,
+                            revision,
+                            forge.kind,
+                            e
+                        );
+                    }
+                }
+                Ok(Ok(None)) => (),
+                Ok(Err(e)) => tracing::warn!("failed to compute commit status: {}", e),
+                Err(e) => tracing::error!("commit status task panicked: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const REV: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    /// Serves one request on a local port with the given status and body,
+    /// returning the URL of the server and the raw request it received
+    async fn mock(status: &str, body: &str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            loop {
+                let n = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length: ")
+                                .and_then(|length| length.parse::<usize>().ok())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    fn forge(kind: ForgeKind, api: String) -> ForgeSettings {
+        ForgeSettings {
+            kind,
+            api,
+            repository: "owner/repo".to_string(),
+            token: "secret".to_string(),
+        }
+    }
+
+    fn status(state: TaskStatusKind) -> Status {
+        Status {
+            context: "Typhon: tests".to_string(),
+            state,
+            target_url: Some("https://typhon.example.com/evaluation/x".to_string()),
+        }
+    }
+
+    /// The request line, the headers in lower case and the JSON body
+    fn parse(request: &str) -> (String, String, serde_json::Value) {
+        let (head, body) = request.split_once("\r\n\r\n").unwrap();
+        let (line, headers) = head.split_once("\r\n").unwrap();
+        (
+            line.to_string(),
+            headers.to_lowercase(),
+            serde_json::from_str(body).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn report_github() {
+        let (api, request) = mock("201 Created", "{}").await;
+        let forge = forge(ForgeKind::GitHub, api);
+        report(
+            &reqwest::Client::new(),
+            &forge,
+            REV,
+            &status(TaskStatusKind::TimedOut),
+        )
+        .await
+        .unwrap();
+        let (line, headers, body) = parse(&request.await.unwrap());
+        assert_eq!(
+            line,
+            format!("POST /repos/owner/repo/statuses/{REV} HTTP/1.1")
+        );
+        assert!(headers.contains("authorization: bearer secret"));
+        assert!(headers.contains("user-agent: typhon"));
+        assert_eq!(body["state"], "failure");
+        assert_eq!(body["context"], "Typhon: tests");
+        assert_eq!(
+            body["target_url"],
+            "https://typhon.example.com/evaluation/x"
+        );
+    }
+
+    #[tokio::test]
+    async fn report_gitea() {
+        let (api, request) = mock("201 Created", "{}").await;
+        let forge = forge(ForgeKind::Gitea, format!("{api}/"));
+        report(
+            &reqwest::Client::new(),
+            &forge,
+            REV,
+            &status(TaskStatusKind::Canceled),
+        )
+        .await
+        .unwrap();
+        let (line, headers, body) = parse(&request.await.unwrap());
+        assert_eq!(
+            line,
+            format!("POST /repos/owner/repo/statuses/{REV} HTTP/1.1")
+        );
+        assert!(headers.contains("authorization: token secret"));
+        assert_eq!(body["state"], "error");
+    }
+
+    #[tokio::test]
+    async fn report_gitlab() {
+        let (api, request) = mock("201 Created", "{}").await;
+        let forge = forge(ForgeKind::GitLab, api);
+        report(
+            &reqwest::Client::new(),
+            &forge,
+            REV,
+            &status(TaskStatusKind::Pending),
+        )
+        .await
+        .unwrap();
+        let (line, headers, body) = parse(&request.await.unwrap());
+        assert_eq!(
+            line,
+            format!("POST /projects/owner%2Frepo/statuses/{REV} HTTP/1.1")
+        );
+        assert!(headers.contains("private-token: secret"));
+        assert_eq!(body["state"], "running");
+        assert_eq!(body["name"], "Typhon: tests");
+    }
+
+    #[tokio::test]
+    async fn report_rejected() {
+        let (api, _) = mock("422 Unprocessable Entity", "{\"message\":\"bad state\"}").await;
+        let forge = forge(ForgeKind::GitHub, api);
+        let res = report(
+            &reqwest::Client::new(),
+            &forge,
+            REV,
+            &status(TaskStatusKind::Success),
+        )
+        .await;
+        match res {
+            Err(Error::Rejected(code, body)) => {
+                assert_eq!(code, reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+                assert!(body.contains("bad state"));
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn request_states() {
+        let client = reqwest::Client::new();
+        let state = |kind, status_kind| {
+            let request = request(
+                &client,
+                &forge(kind, "http://forge".to_string()),
+                REV,
+                &status(status_kind),
+            )
+            .build()
+            .unwrap();
+            let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+            serde_json::from_slice::<serde_json::Value>(body).unwrap()["state"].clone()
+        };
+        assert_eq!(state(ForgeKind::GitHub, TaskStatusKind::Skipped), "success");
+        assert_eq!(
+            state(ForgeKind::GitHub, TaskStatusKind::DependencyFailed),
+            "failure"
+        );
+        assert_eq!(
+            state(ForgeKind::GitHub, TaskStatusKind::InfrastructureError),
+            "error"
+        );
+        assert_eq!(state(ForgeKind::GitLab, TaskStatusKind::Skipped), "skipped");
+        assert_eq!(
+            state(ForgeKind::GitLab, TaskStatusKind::InfrastructureError),
+            "failed"
+        );
+        assert_eq!(
+            state(ForgeKind::GitLab, TaskStatusKind::Canceled),
+            "canceled"
+        );
+    }
+
+    #[tokio::test]
+    async fn compare_truncated() {
+        let (api, request) = mock(
+            "200 OK",
+            "{\"total_commits\":300,\"commits\":[{\"sha\":\"a\"},{\"sha\":\"b\"}]}",
+        )
+        .await;
+        let forge = forge(ForgeKind::GitHub, api);
+        let res = super::revisions(&reqwest::Client::new(), &forge, "a", REV).await;
+        let request = request.await.unwrap();
+        assert_eq!(
+            request.lines().next(),
+            Some(format!("GET /repos/owner/repo/compare/a...{REV} HTTP/1.1").as_str())
+        );
+        assert!(matches!(res, Err(Error::Truncated(2, 300))));
+    }
+
+    #[test]
+    fn revisions() {
+        assert_eq!(revision(&format!("github:owner/repo/{REV}")), Some(REV));
+        assert_eq!(
+            revision(&format!("gitlab:owner/repo/{REV}?dir=sub")),
+            Some(REV)
+        );
+        assert_eq!(
+            revision(&format!("git+https://example.com/repo?ref=main&rev={REV}")),
+            Some(REV)
+        );
+        assert_eq!(revision("github:owner/repo"), None);
+        assert_eq!(revision("github:owner/repo/main"), None);
+        assert_eq!(revision("git+https://example.com/repo?rev=0123abc"), None);
+        assert_eq!(revision(&format!("path:/tmp/{REV}")), None);
+    }
+}
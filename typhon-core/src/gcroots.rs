@@ -3,8 +3,9 @@ use crate::schema;
 use crate::Conn;
 
 use diesel::prelude::*;
+use typhon_types::data::EvaluationOutcome;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{remove_dir_all, rename, DirBuilder};
 use std::os::unix::fs::symlink;
 use std::path::Path;
@@ -35,33 +36,95 @@ impl From<nix::Error> for Error {
     }
 }
 
-allow_columns_to_appear_in_same_group_by_clause!(
-    schema::jobs::out,
-    schema::jobs::drv,
-    schema::jobsets::name,
-);
-
 fn update_aux(conn: &mut Conn) -> Result<(), Error> {
     // collect all gcroots from the database
     let mut gcroots: HashSet<String> = HashSet::new();
-    let mut res_1 = schema::evaluations::table
-        .inner_join(schema::jobs::table)
+
+    // evaluations of closed jobsets, and those beyond the number a jobset
+    // keeps, are left to the garbage collector
+    let mut counts: HashMap<i32, i32> = HashMap::new();
+    let mut kept: HashSet<i32> = schema::evaluations::table
+        .inner_join(
+            schema::jobsets::table.on(schema::evaluations::jobset_name
+                .eq(schema::jobsets::name)
+                .and(schema::evaluations::project_id.eq(schema::jobsets::project_id))),
+        )
+        .filter(schema::jobsets::time_closed.is_null())
+        .order(schema::evaluations::time_created.desc())
+        .select((
+            schema::evaluations::id,
+            schema::jobsets::id,
+            schema::jobsets::keep,
+        ))
+        .load::<(i32, i32, Option<i32>)>(conn)?
+        .into_iter()
+        .filter(|(_, jobset_id, keep)| {
+            let count = counts.entry(*jobset_id).or_default();
+            *count += 1;
+            keep.is_none_or(|keep| *count <= keep)
+        })
+        .map(|(evaluation_id, _, _)| evaluation_id)
+        .collect();
+
+    // the latest evaluation of a jobset that passed, and the latest successful
+    // instance of each of its jobs, are kept regardless, since they are
+    // served by the `latest` routes
+    let mut latest: HashSet<i32> = HashSet::new();
+    kept.extend(
+        schema::evaluations::table
+            .inner_join(
+                schema::jobsets::table.on(schema::evaluations::jobset_name
+                    .eq(schema::jobsets::name)
+                    .and(schema::evaluations::project_id.eq(schema::jobsets::project_id))),
+            )
+            .filter(schema::jobsets::time_closed.is_null())
+            .filter(schema::evaluations::bisection.eq(false))
+            .filter(
+                schema::evaluations::gate
+                    .eq(serde_json::to_string(&EvaluationOutcome::Pass).unwrap()),
+            )
+            .order(schema::evaluations::time_created.desc())
+            .select((schema::evaluations::id, schema::jobsets::id))
+            .load::<(i32, i32)>(conn)?
+            .into_iter()
+            .filter(|(_, jobset_id)| latest.insert(*jobset_id))
+            .map(|(evaluation_id, _)| evaluation_id),
+    );
+
+    // the kept evaluations are filtered here rather than in SQL, whose
+    // number of bound variables is limited
+    let mut res_1: HashSet<(String, String)> = schema::jobs::table
         .inner_join(
-            schema::jobsets::table.on(schema::evaluations::jobset_name.eq(schema::jobsets::name)),
+            schema::evaluations::table.inner_join(
+                schema::jobsets::table.on(schema::evaluations::jobset_name
+                    .eq(schema::jobsets::name)
+                    .and(schema::evaluations::project_id.eq(schema::jobsets::project_id))),
+            ),
         )
-        .group_by((schema::jobs::out, schema::jobs::drv, schema::jobsets::name))
+        .filter(schema::jobsets::time_closed.is_null())
         .select((
+            schema::jobs::evaluation_id,
             schema::jobs::out,
             schema::jobs::drv,
-            schema::jobsets::name,
-            diesel::dsl::max(schema::evaluations::time_created),
         ))
-        .load::<(String, String, String, Option<i64>)>(conn)?;
+        .load::<(i32, String, String)>(conn)?
+        .into_iter()
+        .filter(|(evaluation_id, _, _)| kept.contains(evaluation_id))
+        .map(|(_, out, drv)| (out, drv))
+        .collect();
+    res_1.extend(
+        schema::latest_jobs::table
+            .inner_join(schema::jobs::table)
+            .inner_join(schema::jobsets::table)
+            .filter(schema::jobsets::time_closed.is_null())
+            .select((schema::jobs::out, schema::jobs::drv))
+            .load::<(String, String)>(conn)?,
+    );
     let mut res_2 = schema::projects::table
         .select(schema::projects::actions_path)
         .load::<Option<String>>(conn)?;
 
-    for (path, drv, _, _) in res_1.drain(..) {
+    for (path, drv) in res_1 {
         if let Ok(deps) = nix::dependencies(&drv) {
             for dep in deps {
                 gcroots.insert(dep);
@@ -1,14 +1,11 @@
 use crate::error::Error;
 use crate::handles;
-use crate::log_event;
 use crate::models;
 use crate::responses;
 use crate::runs;
 use crate::schema;
 use crate::Conn;
 
-use typhon_types::*;
-
 use diesel::prelude::*;
 use time::OffsetDateTime;
 
@@ -58,7 +55,8 @@ impl Job {
         .ok_or(Error::JobNotFound(handle))
     }
 
-    /** Create a new run in the database, without running it. */
+    /** Create a new run in the database, without running it nor logging
+     * it, as the caller may not have committed it yet. */
     pub fn new_run(&self, conn: &mut Conn) -> Result<runs::Run, Error> {
         let run = conn.transaction::<models::Run, Error, _>(|conn| {
             let num = self.job.tries + 1;
@@ -78,21 +76,23 @@ impl Job {
             begin: None,
             end: None,
             build: None,
+            reruns: Vec::new(),
             project: self.project.clone(),
             evaluation: self.evaluation.clone(),
             job: self.job.clone(),
             run,
         };
-        log_event(Event::RunNew(run.handle()));
         Ok(run)
     }
 
-    pub fn rerun(&self, conn: &mut Conn) -> Result<(), Error> {
+    /** Create a new run for the job, to be run by the caller. */
+    pub fn rerun(&self, conn: &mut Conn) -> Result<runs::Run, Error> {
         // TODO
         // We should only allow rerunning a job when no other run is pending for
         // that job. But we first need to rework runs, as it is currently hard
         // to know wether a run is finished or not.
-        self.new_run(conn)?;
-        Ok(())
+        let run = self.new_run(conn)?;
+        run.log_new();
+        Ok(run)
     }
 }
@@ -1,17 +1,28 @@
+use crate::bisections;
 use crate::error::Error;
 use crate::evaluations;
 use crate::gcroots;
 use crate::models;
 use crate::nix;
 use crate::schema;
+use crate::timestamp_in;
 use crate::Conn;
+use crate::POOL;
+use crate::RUNTIME;
 use crate::{handles, responses};
 use crate::{log_event, Event};
 
-use typhon_types::requests::JobsetDecl;
+use typhon_types::data::EvaluationOutcome;
+use typhon_types::requests::{JobsetDecl, QuarantineDecl, RetryPolicy};
 
 use diesel::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
 use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// How often expired jobsets are looked for, in seconds
+const COLLECT_INTERVAL: u64 = 3600;
 
 #[derive(Clone)]
 pub struct Jobset {
@@ -20,15 +31,90 @@ pub struct Jobset {
 }
 
 impl Jobset {
-    pub fn delete(&self, conn: &mut Conn) -> Result<(), Error> {
-        diesel::delete(schema::jobsets::table.find(&self.jobset.id)).execute(conn)?;
+    /// Closes the jobset. It cannot be evaluated anymore and its evaluations
+    /// are no longer protected from garbage collection, but they remain
+    /// browsable.
+    pub fn close(&self, conn: &mut Conn) -> Result<(), Error> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        diesel::update(&self.jobset)
+            .set(schema::jobsets::time_closed.eq(now))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Whether the jobset was closed because it expired, rather than because
+    /// it was deleted or no longer declared
+    pub fn expired(&self) -> bool {
+        match (self.jobset.time_closed, self.jobset.expiry) {
+            (Some(time_closed), Some(expiry)) => {
+                self.jobset.time_active.saturating_add(expiry) < time_closed
+            }
+            _ => false,
+        }
+    }
+
+    /// Creates a jobset, or updates its declaration and reopens it if it
+    /// already exists
+    pub fn declare(
+        conn: &mut Conn,
+        project: &models::Project,
+        name: &str,
+        decl: &JobsetDecl,
+    ) -> Result<(), Error> {
+        let kind = serde_json::to_string(&decl.kind).unwrap();
+        let retry = decl
+            .retry
+            .as_ref()
+            .map(|retry| serde_json::to_string(retry).unwrap());
+        let expiry = decl
+            .expiry
+            .map(|expiry| i64::try_from(expiry).map_err(|_| Error::InvalidDuration(expiry)))
+            .transpose()?;
+        let keep = decl
+            .keep
+            .map(|keep| i32::try_from(keep).map_err(|_| Error::InvalidKeep(keep)))
+            .transpose()?;
+        let new_jobset = models::NewJobset {
+            checked_jobs: decl.checked_jobs.as_deref(),
+            description: &decl.description,
+            enabled: decl.enabled,
+            expiry,
+            flake: decl.flake,
+            hidden: decl.hidden,
+            keep,
+            kind: &kind,
+            name,
+            priority: decl.priority,
+            project_id: project.id,
+            required_jobs: decl.required_jobs.as_deref(),
+            retry: retry.as_deref(),
+            time_active: OffsetDateTime::now_utc().unix_timestamp(),
+            url: &decl.url,
+        };
+        diesel::insert_into(schema::jobsets::table)
+            .values(&new_jobset)
+            .on_conflict((schema::jobsets::project_id, schema::jobsets::name))
+            .do_update()
+            .set((&new_jobset, schema::jobsets::time_closed.eq(None::<i64>)))
+            .execute(conn)?;
         Ok(())
     }
 
     pub fn evaluate(&self, conn: &mut Conn, force: bool) -> Result<handles::Evaluation, Error> {
         use crate::tasks;
 
-        let url = nix::lock(&self.jobset.url)?;
+        if self.jobset.time_closed.is_some() {
+            return Err(Error::JobsetClosed(self.handle()));
+        }
+        if !self.jobset.enabled {
+            return Err(Error::JobsetDisabled(self.handle()));
+        }
+
+        let (url, flake_lock) = nix::lock_with_inputs(&self.jobset.url)?;
+
+        diesel::update(&self.jobset)
+            .set(schema::jobsets::time_active.eq(OffsetDateTime::now_utc().unix_timestamp()))
+            .execute(conn)?;
 
         let preexisting = schema::evaluations::table
             .inner_join(schema::tasks::table)
@@ -43,19 +129,123 @@ impl Jobset {
                 evaluation,
                 task: tasks::Task { task },
             },
-            _ => self.new_evaluation(conn, &url)?,
+            _ => self.new_evaluation(conn, &url, &flake_lock, false)?,
         };
 
         Ok(evaluation.handle())
     }
 
+    /// Evaluates a past revision of the jobset to bisect a failure, reusing
+    /// the evaluation of the same locked URL if there is one
+    pub fn evaluate_revision(
+        &self,
+        conn: &mut Conn,
+        url: &String,
+    ) -> Result<evaluations::Evaluation, Error> {
+        use crate::tasks;
+
+        if self.jobset.time_closed.is_some() {
+            return Err(Error::JobsetClosed(self.handle()));
+        }
+
+        let (url, flake_lock) = nix::lock_with_inputs(url)?;
+
+        let preexisting = schema::evaluations::table
+            .inner_join(schema::tasks::table)
+            .filter(schema::evaluations::project_id.eq(self.project.id))
+            .filter(schema::evaluations::jobset_name.eq(&self.jobset.name))
+            .filter(schema::evaluations::url.eq(&url))
+            .first::<(models::Evaluation, models::Task)>(conn)
+            .optional()?;
+
+        match preexisting {
+            Some((evaluation, task)) => Ok(evaluations::Evaluation {
+                project: self.project.clone(),
+                evaluation,
+                task: tasks::Task { task },
+            }),
+            None => self.new_evaluation(conn, &url, &flake_lock, true),
+        }
+    }
+
+    /// Starts looking for the evaluation where a job started failing
+    pub fn bisect(&self, conn: &mut Conn, job: &str) -> Result<responses::Bisection, Error> {
+        bisections::start(conn, self, job)?.info(conn)
+    }
+
+    /// The latest search for the evaluation where a job started failing
+    pub fn bisection(
+        &self,
+        conn: &mut Conn,
+        job: &str,
+    ) -> Result<Option<responses::Bisection>, Error> {
+        bisections::latest(conn, self, job)?
+            .map(|bisection| bisection.info(conn))
+            .transpose()
+    }
+
     pub fn decl(&self) -> JobsetDecl {
         JobsetDecl {
             flake: self.jobset.flake,
             url: self.jobset.url.clone(),
+            kind: self.kind(),
+            expiry: self.jobset.expiry.map(|expiry| expiry as u64),
+            description: self.jobset.description.clone(),
+            enabled: self.jobset.enabled,
+            hidden: self.jobset.hidden,
+            keep: self.jobset.keep.map(|keep| keep as u32),
+            priority: self.jobset.priority,
+            checked_jobs: self.jobset.checked_jobs.clone(),
+            retry: self.retry(),
+            required_jobs: self.jobset.required_jobs.clone(),
         }
     }
 
+    /// The jobs of the jobset that passed only after being retried, the most
+    /// often flaky first
+    pub fn flaky_jobs(&self, conn: &mut Conn) -> Result<Vec<responses::FlakyJob>, Error> {
+        use std::collections::BTreeMap;
+
+        let jobs = schema::jobs::table
+            .inner_join(schema::evaluations::table)
+            .filter(schema::evaluations::project_id.eq(self.project.id))
+            .filter(schema::evaluations::jobset_name.eq(&self.jobset.name))
+            .filter(schema::evaluations::bisection.eq(false))
+            .order(schema::evaluations::time_created.asc())
+            .select((
+                schema::jobs::name,
+                schema::jobs::flaky,
+                schema::evaluations::uuid,
+            ))
+            .load::<(String, bool, String)>(conn)?;
+        let mut report: BTreeMap<String, (u32, u32, Option<String>)> = BTreeMap::new();
+        for (name, flaky, uuid) in jobs {
+            let (flaky_count, total, last) = report.entry(name).or_default();
+            *total += 1;
+            if flaky {
+                *flaky_count += 1;
+                *last = Some(uuid);
+            }
+        }
+        let mut flaky_jobs: Vec<responses::FlakyJob> = report
+            .into_iter()
+            .filter_map(|(name, (flaky, total, last))| {
+                let evaluation = handles::evaluation(Uuid::from_str(&last?).ok()?);
+                Some(responses::FlakyJob {
+                    last: handles::Job {
+                        evaluation,
+                        name: name.clone(),
+                    },
+                    name,
+                    flaky,
+                    total,
+                })
+            })
+            .collect();
+        flaky_jobs.sort_by_key(|job| std::cmp::Reverse(job.flaky));
+        Ok(flaky_jobs)
+    }
+
     pub fn get(conn: &mut Conn, handle: &handles::Jobset) -> Result<Self, Error> {
         let (jobset, project) = schema::jobsets::table
             .inner_join(schema::projects::table)
@@ -76,21 +266,287 @@ impl Jobset {
         }
     }
 
-    pub fn info(&self) -> responses::JobsetInfo {
-        responses::JobsetInfo {
+    pub fn info(&self) -> Result<responses::JobsetInfo, Error> {
+        Ok(responses::JobsetInfo {
             handle: self.handle(),
-            flake: self.jobset.flake,
-            url: self.jobset.url.clone(),
+            decl: self.decl(),
+            time_closed: self
+                .jobset
+                .time_closed
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+        })
+    }
+
+    /// A page of the instances of a job in the evaluations of the jobset, the
+    /// newest first
+    pub fn job_history(
+        &self,
+        conn: &mut Conn,
+        job: &str,
+        offset: u32,
+        limit: u8,
+    ) -> Result<responses::JobHistory, Error> {
+        let query = schema::jobs::table
+            .inner_join(schema::evaluations::table)
+            .filter(schema::evaluations::project_id.eq(self.project.id))
+            .filter(schema::evaluations::jobset_name.eq(&self.jobset.name))
+            .filter(schema::evaluations::bisection.eq(false))
+            .filter(schema::jobs::name.eq(job));
+        let total = query.count().get_result::<i64>(conn)?;
+        // one more instance is loaded to tell whether the derivation of the
+        // oldest one of the page changed
+        let instances = query
+            .order(schema::evaluations::time_created.desc())
+            .offset(offset as i64)
+            .limit(limit as i64 + 1)
+            .select((
+                schema::evaluations::id,
+                schema::evaluations::uuid,
+                schema::evaluations::time_created,
+                schema::jobs::drv,
+            ))
+            .load::<(i32, String, i64, String)>(conn)?;
+        let page = &instances[..instances.len().min(limit as usize)];
+        let evaluation_handles: HashMap<i32, handles::Evaluation> = page
+            .iter()
+            .map(|(id, uuid, _, _)| (*id, handles::evaluation(Uuid::from_str(uuid).unwrap())))
+            .collect();
+        let mut infos: HashMap<i32, responses::JobInfo> = evaluations::Evaluation::jobs_of(
+            &handles::project(self.project.name.clone()),
+            &evaluation_handles,
+            Some(job.to_string()),
+            conn,
+        )?
+        .into_iter()
+        .collect();
+        let mut entries = Vec::new();
+        for (i, (evaluation_id, _, time_created, drv)) in page.iter().enumerate() {
+            let evaluation = evaluation_handles[evaluation_id].clone();
+            let info = infos.remove(evaluation_id);
+            let build_duration = info
+                .as_ref()
+                .and_then(|info| info.last_run.build.as_ref())
+                .and_then(|build| match build.status.times() {
+                    (Some(start), Some(end)) => Some((end - start).whole_seconds()),
+                    _ => None,
+                });
+            entries.push(responses::JobHistoryEntry {
+                handle: handles::Job {
+                    evaluation,
+                    name: job.to_string(),
+                },
+                time_created: OffsetDateTime::from_unix_timestamp(*time_created)?,
+                status: info
+                    .as_ref()
+                    .map(responses::TaskStatus::from)
+                    .unwrap_or_default(),
+                build_duration,
+                drv: drv.clone(),
+                drv_changed: instances
+                    .get(i + 1)
+                    .is_some_and(|(_, _, _, previous)| previous != drv),
+            });
+        }
+        Ok(responses::JobHistory {
+            total: total as u32,
+            entries,
+        })
+    }
+
+    /// The newest evaluation of the jobset that passed its gate, evaluations
+    /// of past revisions made for bisections aside
+    pub fn latest(&self, conn: &mut Conn) -> Result<handles::Evaluation, Error> {
+        let uuid = schema::evaluations::table
+            .filter(schema::evaluations::project_id.eq(self.project.id))
+            .filter(schema::evaluations::jobset_name.eq(&self.jobset.name))
+            .filter(schema::evaluations::bisection.eq(false))
+            .filter(
+                schema::evaluations::gate
+                    .eq(serde_json::to_string(&EvaluationOutcome::Pass).unwrap()),
+            )
+            .order(schema::evaluations::time_created.desc())
+            .select(schema::evaluations::uuid)
+            .first::<String>(conn)
+            .optional()?
+            .ok_or(Error::NoPassingEvaluation(self.handle()))?;
+        Ok(handles::evaluation(Uuid::from_str(&uuid).unwrap()))
+    }
+
+    /// The newest instance of a job of the jobset that succeeded
+    pub fn latest_job(&self, conn: &mut Conn, name: &str) -> Result<handles::Job, Error> {
+        let uuid = schema::latest_jobs::table
+            .inner_join(schema::jobs::table.inner_join(schema::evaluations::table))
+            .filter(schema::latest_jobs::jobset_id.eq(self.jobset.id))
+            .filter(schema::latest_jobs::job_name.eq(name))
+            .select(schema::evaluations::uuid)
+            .first::<String>(conn)
+            .optional()?
+            .ok_or(Error::NoSuccessfulJob(self.handle(), name.to_string()))?;
+        Ok(handles::Job {
+            evaluation: handles::evaluation(Uuid::from_str(&uuid).unwrap()),
+            name: name.to_string(),
+        })
+    }
+
+    /// The statuses of the jobs of the `count` latest evaluations of the
+    /// jobset, with the jobs of all evaluations loaded in a single query
+    pub fn matrix(&self, conn: &mut Conn, count: u8) -> Result<responses::JobsetMatrix, Error> {
+        use std::collections::BTreeMap;
+
+        let evaluations = schema::evaluations::table
+            .filter(schema::evaluations::project_id.eq(self.project.id))
+            .filter(schema::evaluations::jobset_name.eq(&self.jobset.name))
+            .filter(schema::evaluations::bisection.eq(false))
+            .order(schema::evaluations::time_created.desc())
+            .limit(count as i64)
+            .load::<models::Evaluation>(conn)?
+            .into_iter()
+            .map(|evaluation| {
+                Ok((
+                    evaluation.id,
+                    responses::MatrixEvaluation {
+                        handle: handles::evaluation(Uuid::from_str(&evaluation.uuid).unwrap()),
+                        outcome: evaluation.outcome(),
+                        time_created: OffsetDateTime::from_unix_timestamp(evaluation.time_created)?,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let columns: HashMap<i32, usize> = evaluations
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, i))
+            .collect();
+        let evaluation_handles: HashMap<i32, handles::Evaluation> = evaluations
+            .iter()
+            .map(|(id, evaluation)| (*id, evaluation.handle.clone()))
+            .collect();
+        let mut rows: BTreeMap<String, Vec<Option<(handles::Run, responses::TaskStatus)>>> =
+            BTreeMap::new();
+        for (evaluation_id, job) in evaluations::Evaluation::jobs_of(
+            &handles::project(self.project.name.clone()),
+            &evaluation_handles,
+            None,
+            conn,
+        )? {
+            let status = responses::TaskStatus::from(&job);
+            let cells = rows
+                .entry(job.handle.name)
+                .or_insert_with(|| vec![None; evaluations.len()]);
+            cells[columns[&evaluation_id]] = Some((job.last_run.handle, status));
         }
+        Ok(responses::JobsetMatrix {
+            evaluations: evaluations
+                .into_iter()
+                .map(|(_, evaluation)| evaluation)
+                .collect(),
+            jobs: rows
+                .into_iter()
+                .map(|(name, cells)| responses::MatrixJob { name, cells })
+                .collect(),
+        })
+    }
+
+    /// Quarantines a job, replacing its current quarantine if any
+    pub fn quarantine(&self, conn: &mut Conn, decl: &QuarantineDecl) -> Result<(), Error> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let time_expires = timestamp_in(decl.duration)?;
+        conn.transaction::<(), Error, _>(|conn| {
+            self.lift_quarantine(conn, &decl.job, now)?;
+            diesel::insert_into(schema::quarantines::table)
+                .values(&models::NewQuarantine {
+                    job_name: &decl.job,
+                    jobset_id: self.jobset.id,
+                    reason: &decl.reason,
+                    time_created: now,
+                    time_expires,
+                })
+                .execute(conn)?;
+            Ok(())
+        })?;
+        tracing::info!(
+            "quarantined job {} of jobset {}: {}",
+            decl.job,
+            self.handle(),
+            decl.reason
+        );
+        log_event(Event::ProjectUpdated(self.handle().project));
+        evaluations::update_outcomes(self.project.id, self.jobset.name.clone(), decl.job.clone());
+        Ok(())
+    }
+
+    /// The quarantines of the jobset, the most recent first
+    pub fn quarantines(&self, conn: &mut Conn) -> Result<Vec<responses::Quarantine>, Error> {
+        schema::quarantines::table
+            .filter(schema::quarantines::jobset_id.eq(self.jobset.id))
+            .order(schema::quarantines::id.desc())
+            .load::<models::Quarantine>(conn)?
+            .iter()
+            .map(models::Quarantine::info)
+            .collect()
+    }
+
+    /// Lifts the quarantine of a job before it expires
+    pub fn unquarantine(&self, conn: &mut Conn, job: &str) -> Result<(), Error> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if self.lift_quarantine(conn, job, now)? == 0 {
+            return Err(Error::JobNotQuarantined(self.handle(), job.to_string()));
+        }
+        tracing::info!(
+            "lifted quarantine of job {} of jobset {}",
+            job,
+            self.handle()
+        );
+        log_event(Event::ProjectUpdated(self.handle().project));
+        evaluations::update_outcomes(self.project.id, self.jobset.name.clone(), job.to_string());
+        Ok(())
+    }
+
+    fn lift_quarantine(&self, conn: &mut Conn, job: &str, now: i64) -> Result<usize, Error> {
+        Ok(diesel::update(
+            schema::quarantines::table
+                .filter(schema::quarantines::jobset_id.eq(self.jobset.id))
+                .filter(schema::quarantines::job_name.eq(job))
+                .filter(schema::quarantines::time_lifted.is_null())
+                .filter(schema::quarantines::time_expires.gt(now)),
+        )
+        .set(schema::quarantines::time_lifted.eq(now))
+        .execute(conn)?)
+    }
+
+    pub fn kind(&self) -> typhon_types::requests::JobsetKind {
+        serde_json::from_str(&self.jobset.kind).unwrap_or_default()
+    }
+
+    pub fn retry(&self) -> Option<RetryPolicy> {
+        self.jobset
+            .retry
+            .as_ref()
+            .and_then(|retry| serde_json::from_str(retry).ok())
+    }
+
+    /// Updates the declaration of the jobset in place, keeping its
+    /// evaluations
+    pub fn set_decl(&self, conn: &mut Conn, decl: &JobsetDecl) -> Result<(), Error> {
+        if self.jobset.time_closed.is_some() {
+            return Err(Error::JobsetClosed(self.handle()));
+        }
+        Self::declare(conn, &self.project, &self.jobset.name, decl)?;
+        log_event(Event::ProjectUpdated(self.handle().project));
+        gcroots::update(conn);
+        Ok(())
     }
 
     fn new_evaluation(
         &self,
         conn: &mut Conn,
         url: &String,
+        flake_lock: &responses::FlakeLock,
+        bisection: bool,
     ) -> Result<evaluations::Evaluation, Error> {
         use crate::tasks;
-        use uuid::{timestamp, Uuid};
+        use uuid::timestamp;
 
         let evaluation = conn.transaction::<evaluations::Evaluation, Error, _>(|conn| {
             let task = tasks::Task::new(conn)?;
@@ -102,7 +558,9 @@ impl Jobset {
             ));
             let new_evaluation = models::NewEvaluation {
                 actions_path: self.project.actions_path.as_ref().map(|s| s.as_str()),
+                bisection,
                 flake: self.jobset.flake,
+                flake_lock: Some(&serde_json::to_string(flake_lock).unwrap()),
                 jobset_name: &self.jobset.name,
                 project_id: self.project.id,
                 task_id: task.task.id,
@@ -136,6 +594,7 @@ impl Jobset {
 
         log_event(Event::EvaluationNew(evaluation.handle()));
 
+        evaluation.fetch_commit();
         evaluation.task.run(conn, run, finish)?;
 
         gcroots::update(conn);
@@ -143,3 +602,85 @@ impl Jobset {
         Ok(evaluation)
     }
 }
+
+impl models::Quarantine {
+    pub fn info(&self) -> Result<responses::Quarantine, Error> {
+        Ok(responses::Quarantine {
+            job: self.job_name.clone(),
+            reason: self.reason.clone(),
+            time_created: OffsetDateTime::from_unix_timestamp(self.time_created)?,
+            time_expires: OffsetDateTime::from_unix_timestamp(self.time_expires)?,
+            time_lifted: self
+                .time_lifted
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+        })
+    }
+}
+
+/// The active quarantines of a jobset, by job name
+pub fn active_quarantines(
+    conn: &mut Conn,
+    project_id: i32,
+    jobset_name: &str,
+) -> Result<HashMap<String, responses::Quarantine>, Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    Ok(schema::quarantines::table
+        .inner_join(schema::jobsets::table)
+        .filter(schema::jobsets::project_id.eq(project_id))
+        .filter(schema::jobsets::name.eq(jobset_name))
+        .filter(schema::quarantines::time_lifted.is_null())
+        .filter(schema::quarantines::time_expires.gt(now))
+        .select(schema::quarantines::all_columns)
+        .load::<models::Quarantine>(conn)?
+        .iter()
+        // quarantines with timestamps out of range are ignored rather than
+        // failing every evaluation of the jobset
+        .filter_map(|quarantine| Some((quarantine.job_name.clone(), quarantine.info().ok()?)))
+        .collect())
+}
+
+/// Closes the open jobsets that have not been evaluated for longer than their
+/// expiry
+pub fn collect(conn: &mut Conn) -> Result<(), Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let expired = schema::jobsets::table
+        .inner_join(schema::projects::table)
+        .filter(schema::jobsets::time_closed.is_null())
+        .filter(schema::jobsets::expiry.is_not_null())
+        .load::<(models::Jobset, models::Project)>(conn)?
+        .into_iter()
+        .filter(|(jobset, _)| {
+            jobset
+                .expiry
+                .is_some_and(|expiry| jobset.time_active.saturating_add(expiry) < now)
+        })
+        .map(|(jobset, project)| Jobset { jobset, project })
+        .collect::<Vec<_>>();
+    if expired.is_empty() {
+        return Ok(());
+    }
+    for jobset in expired.iter() {
+        tracing::info!("closing expired jobset {}", jobset.handle());
+        jobset.close(conn)?;
+        log_event(Event::ProjectUpdated(jobset.handle().project));
+    }
+    gcroots::update(conn);
+    Ok(())
+}
+
+/// Periodically closes expired jobsets
+pub fn collect_periodically() {
+    RUNTIME.spawn(async {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(COLLECT_INTERVAL));
+        loop {
+            interval.tick().await;
+            let res = tokio::task::spawn_blocking(|| collect(&mut POOL.get().unwrap())).await;
+            match res {
+                Ok(Ok(())) => (),
+                Ok(Err(e)) => tracing::error!("failed to collect expired jobsets: {}", e),
+                Err(e) => tracing::error!("jobset collection task panicked: {}", e),
+            }
+        }
+    });
+}
@@ -1,9 +1,12 @@
 #![feature(impl_trait_in_fn_trait_return)]
 
 mod actions;
+mod bisections;
 mod builds;
+mod deliveries;
 mod evaluations;
 mod events;
+mod forges;
 mod gcroots;
 mod jobs;
 mod jobsets;
@@ -30,6 +33,7 @@ pub use crate::actions::webhooks;
 
 use actions::Action;
 use builds::Build;
+use deliveries::Delivery;
 use error::Error;
 use evaluations::Evaluation;
 use jobs::Job;
@@ -52,6 +56,15 @@ use std::sync::{LazyLock, OnceLock};
 #[derive(Debug)]
 pub struct Settings {
     pub password: PasswordHash<'static>,
+    /// The public URL of the instance, used to link statuses reported to
+    /// forges
+    pub url: Option<String>,
+    /// The default maximum duration of builds, in seconds
+    pub build_timeout: Option<u64>,
+    /// The default maximum duration of builds without any output, in seconds
+    pub max_silent_time: Option<u64>,
+    /// The maximum number of builds running at once, unlimited by default
+    pub max_builds: Option<usize>,
 }
 
 const _: () = {
@@ -67,6 +80,20 @@ const _: () = {
     }
 };
 
+/// The timestamp `duration` seconds from now, rejecting durations that do not
+/// lead to a representable date
+fn timestamp_in(duration: u64) -> Result<i64, Error> {
+    i64::try_from(duration)
+        .ok()
+        .and_then(|seconds| {
+            time::OffsetDateTime::now_utc()
+                .unix_timestamp()
+                .checked_add(seconds)
+        })
+        .filter(|timestamp| time::OffsetDateTime::from_unix_timestamp(*timestamp).is_ok())
+        .ok_or(Error::InvalidDuration(duration))
+}
+
 fn verify_password(password: &[u8]) -> bool {
     use argon2::{Argon2, PasswordVerifier};
     Argon2::default()
@@ -132,9 +159,24 @@ impl User {
 pub fn authorize_request(user: &User, req: &requests::Request) -> bool {
     use requests::*;
     match req {
+        // deliveries expose the raw headers sent by forges, including tokens
+        Request::Search(search::Request {
+            kind: search::Kind::Deliveries(_),
+            ..
+        }) => user.is_admin(),
         Request::Search { .. }
         | Request::Project(_, Project::Info)
+        | Request::Project(_, Project::NonDeterministicBuilds)
+        | Request::Jobset(_, Jobset::Bisection(_))
         | Request::Jobset(_, Jobset::Info)
+        | Request::Jobset(_, Jobset::FlakyJobs)
+        | Request::Jobset(_, Jobset::JobHistory { .. })
+        | Request::Jobset(_, Jobset::Latest)
+        | Request::Jobset(_, Jobset::LatestJob(_))
+        | Request::Jobset(_, Jobset::Matrix(_))
+        | Request::Jobset(_, Jobset::Quarantines)
+        | Request::Evaluation(_, Evaluation::Compare(_))
+        | Request::Evaluation(_, Evaluation::Gate)
         | Request::Evaluation(_, Evaluation::Info)
         | Request::Job(_, Job::Info)
         | Request::Run(_, Run::Info)
@@ -168,6 +210,11 @@ pub fn handle_request_aux(
             let project = Project::get(conn, &project_handle)?;
             match req {
                 requests::Project::Info => return Ok(Response::ProjectInfo(project.info(conn)?)),
+                requests::Project::NonDeterministicBuilds => {
+                    return Ok(Response::ProjectNonDeterministicBuilds(
+                        project.non_deterministic_builds(conn)?,
+                    ))
+                }
                 requests::Project::Refresh => project.refresh(conn)?,
                 requests::Project::SetDecl(decl) => project.set_decl(conn, decl)?,
                 requests::Project::UpdateJobsets => project.update_jobsets(conn)?,
@@ -175,17 +222,67 @@ pub fn handle_request_aux(
                     project.new_jobset(conn, name, decl)?
                 }
                 requests::Project::DeleteJobset { name } => project.delete_jobset(conn, name)?,
+                requests::Project::RotateKey { grace_period } => {
+                    project.rotate_key(conn, *grace_period)?
+                }
+                requests::Project::EncryptSecrets(secrets) => {
+                    return Ok(Response::ProjectEncryptSecrets(
+                        project.encrypt_secrets(secrets)?,
+                    ))
+                }
+                requests::Project::SetSandbox(policy) => project.set_sandbox(conn, policy)?,
+                requests::Project::RunAction { name, input } => {
+                    return Ok(Response::ProjectRunAction(
+                        project.run_action(conn, name, input)?,
+                    ))
+                }
+                requests::Project::SetWebhookSecret(secret) => {
+                    project.set_webhook_secret(conn, secret)?
+                }
+                requests::Project::SetForge(forge) => project.set_forge(conn, forge)?,
             };
             Response::Ok
         }
         requests::Request::Jobset(jobset_handle, req) => {
             let jobset = Jobset::get(conn, &jobset_handle)?;
             match req {
+                requests::Jobset::Bisect(job) => {
+                    Response::JobsetBisection(Some(jobset.bisect(conn, job)?))
+                }
+                requests::Jobset::Bisection(job) => {
+                    Response::JobsetBisection(jobset.bisection(conn, job)?)
+                }
                 requests::Jobset::Evaluate(force) => {
                     let evaluation_handle = jobset.evaluate(conn, *force)?;
                     Response::JobsetEvaluate(evaluation_handle)
                 }
-                requests::Jobset::Info => Response::JobsetInfo(jobset.info()),
+                requests::Jobset::FlakyJobs => Response::JobsetFlakyJobs(jobset.flaky_jobs(conn)?),
+                requests::Jobset::Info => Response::JobsetInfo(jobset.info()?),
+                requests::Jobset::JobHistory { job, offset, limit } => {
+                    Response::JobsetJobHistory(jobset.job_history(conn, job, *offset, *limit)?)
+                }
+                requests::Jobset::Latest => Response::JobsetLatest(jobset.latest(conn)?),
+                requests::Jobset::LatestJob(name) => {
+                    Response::JobsetLatestJob(jobset.latest_job(conn, name)?)
+                }
+                requests::Jobset::Matrix(count) => {
+                    Response::JobsetMatrix(jobset.matrix(conn, *count)?)
+                }
+                requests::Jobset::Quarantine(decl) => {
+                    jobset.quarantine(conn, decl)?;
+                    Response::Ok
+                }
+                requests::Jobset::Quarantines => {
+                    Response::JobsetQuarantines(jobset.quarantines(conn)?)
+                }
+                requests::Jobset::SetDecl(decl) => {
+                    jobset.set_decl(conn, decl)?;
+                    Response::Ok
+                }
+                requests::Jobset::Unquarantine(job) => {
+                    jobset.unquarantine(conn, job)?;
+                    Response::Ok
+                }
             }
         }
         requests::Request::Evaluation(evaluation_handle, req) => {
@@ -195,7 +292,16 @@ pub fn handle_request_aux(
                     evaluation.cancel();
                     Response::Ok
                 }
+                requests::Evaluation::Compare(other) => {
+                    Response::EvaluationCompare(evaluation.compare(conn, other.as_ref())?)
+                }
+                requests::Evaluation::Gate => {
+                    Response::EvaluationGate(evaluation.evaluation.gate())
+                }
                 requests::Evaluation::Info => Response::EvaluationInfo(evaluation.info(conn)?),
+                requests::Evaluation::RerunFailed(filter) => {
+                    Response::EvaluationRerunFailed(evaluation.rerun_failed(conn, filter)?)
+                }
             }
         }
         requests::Request::Job(job_handle, req) => {
@@ -203,7 +309,7 @@ pub fn handle_request_aux(
             match req {
                 requests::Job::Info => Response::JobInfo(job.info(conn)?),
                 requests::Job::Rerun => {
-                    job.rerun(conn)?;
+                    job.rerun(conn)?.run(conn)?;
                     Response::Ok
                 }
             }
@@ -220,6 +326,15 @@ pub fn handle_request_aux(
                 requests::Action::Info => Response::ActionInfo(action.info()),
             }
         }
+        requests::Request::Delivery(delivery_handle, req) => {
+            let delivery = Delivery::get(conn, &delivery_handle)?;
+            match req {
+                requests::Delivery::Info => Response::DeliveryInfo(delivery.info(conn)?),
+                requests::Delivery::Replay => {
+                    Response::DeliveryReplay(delivery.replay(conn)?.handle())
+                }
+            }
+        }
         requests::Request::Run(run_handle, req) => {
             let run = Run::get(conn, &run_handle)?;
             match req {
@@ -228,6 +343,14 @@ pub fn handle_request_aux(
                 //    Response::Ok
                 //}
                 requests::Run::Info => Response::RunInfo(run.info()),
+                requests::Run::RerunBegin => {
+                    run.rerun_begin(conn)?;
+                    Response::Ok
+                }
+                requests::Run::RerunEnd => {
+                    run.rerun_end(conn)?;
+                    Response::Ok
+                }
             }
         }
         requests::Request::Login { password } => {
@@ -281,11 +404,20 @@ pub fn log(handle: handles::Log) -> Result<Option<impl Stream<Item = String>>, E
     }
 }
 
+/// The secret used to authenticate the webhook deliveries of a project, if any
+pub fn webhook_secret(project_handle: &handles::Project) -> Result<Option<String>, Error> {
+    let mut conn = POOL.get().unwrap();
+    let project = projects::Project::get(&mut conn, project_handle)?;
+    Ok(project.project.webhook_secret)
+}
+
+/// Records a webhook delivery, which is processed in the background
 pub fn webhook(
     project_handle: handles::Project,
     input: actions::webhooks::Input,
-) -> Result<(), Error> {
+) -> Result<handles::Delivery, Error> {
     let mut conn = POOL.get().unwrap();
+    let input = input.without_secrets();
 
     tracing::debug!("handling webhook {:?}", input);
 
@@ -296,7 +428,7 @@ pub fn webhook(
         e
     })?;
 
-    project.webhook(&mut conn, input).map_err(|e| {
+    let delivery = project.webhook(&mut conn, input).map_err(|e| {
         if e.is_internal() {
             tracing::error!(
                 "webhook for project {} raised error: {:?}",
@@ -313,7 +445,7 @@ pub fn webhook(
         e
     })?;
 
-    Ok(())
+    Ok(delivery)
 }
 
 pub async fn shutdown() {
@@ -349,10 +481,22 @@ fn pool() -> DbPool {
     pool
 }
 
-pub fn init(password: &String) {
+pub fn init(
+    password: &String,
+    url: Option<String>,
+    build_timeout: Option<u64>,
+    max_silent_time: Option<u64>,
+    max_builds: Option<usize>,
+) {
     let password = Box::leak(Box::new(password.clone()));
     let password = PasswordHash::new(password).expect("Unable to parse the password hash");
-    Settings::init(Settings { password });
+    Settings::init(Settings {
+        password,
+        url,
+        build_timeout,
+        max_silent_time,
+        max_builds,
+    });
 
     // Force database migrations
     let _ = LazyLock::force(&POOL);
@@ -364,4 +508,9 @@ pub fn init(password: &String) {
     let _ = LazyLock::force(&LOGS);
     let _ = LazyLock::force(&EVENT_LOGGER);
     let _ = LazyLock::force(&build_manager::BUILDS);
+
+    forges::listen();
+    evaluations::listen();
+    bisections::listen();
+    jobsets::collect_periodically();
 }
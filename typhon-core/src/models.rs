@@ -1,10 +1,15 @@
 use crate::schema::actions;
+use crate::schema::bisections;
 use crate::schema::builds;
+use crate::schema::deliveries;
 use crate::schema::evaluations;
 use crate::schema::jobs;
 use crate::schema::jobsets;
+use crate::schema::latest_jobs;
 use crate::schema::logs;
 use crate::schema::projects;
+use crate::schema::quarantines;
+use crate::schema::run_actions;
 use crate::schema::runs;
 use crate::schema::tasks;
 
@@ -14,16 +19,23 @@ use diesel::prelude::*;
 #[diesel(table_name = projects)]
 pub struct Project {
     pub actions_path: Option<String>,
+    pub custom_actions: String,
     pub description: String,
     pub flake: bool,
+    pub forge: Option<String>,
     pub homepage: String,
     pub id: i32,
     pub key: String,
+    pub last_refresh_failure: Option<String>,
     pub last_refresh_task_id: Option<i32>,
     pub name: String,
+    pub previous_key: Option<String>,
+    pub previous_key_expiry: Option<i64>,
+    pub sandbox: String,
     pub title: String,
     pub url: String,
     pub url_locked: String,
+    pub webhook_secret: Option<String>,
 }
 
 #[derive(Debug, Insertable)]
@@ -39,19 +51,43 @@ pub struct NewProject<'a> {
 #[diesel(table_name = jobsets)]
 #[diesel(belongs_to(Project))]
 pub struct Jobset {
+    pub checked_jobs: Option<String>,
+    pub description: String,
+    pub enabled: bool,
+    pub expiry: Option<i64>,
     pub flake: bool,
+    pub hidden: bool,
     pub id: i32,
+    pub keep: Option<i32>,
+    pub kind: String,
     pub name: String,
+    pub priority: i32,
     pub project_id: i32,
+    pub required_jobs: Option<String>,
+    pub retry: Option<String>,
+    pub time_active: i64,
+    pub time_closed: Option<i64>,
     pub url: String,
 }
 
-#[derive(Debug, Insertable)]
+#[derive(Debug, Insertable, AsChangeset)]
 #[diesel(table_name = jobsets)]
+#[diesel(treat_none_as_null = true)]
 pub struct NewJobset<'a> {
+    pub checked_jobs: Option<&'a str>,
+    pub description: &'a str,
+    pub enabled: bool,
+    pub expiry: Option<i64>,
     pub flake: bool,
+    pub hidden: bool,
+    pub keep: Option<i32>,
+    pub kind: &'a str,
     pub name: &'a str,
+    pub priority: i32,
     pub project_id: i32,
+    pub required_jobs: Option<&'a str>,
+    pub retry: Option<&'a str>,
+    pub time_active: i64,
     pub url: &'a str,
 }
 
@@ -61,9 +97,16 @@ pub struct NewJobset<'a> {
 #[diesel(belongs_to(Task))]
 pub struct Evaluation {
     pub actions_path: Option<String>,
+    pub bisection: bool,
+    pub commit_author: Option<String>,
+    pub commit_subject: Option<String>,
     pub flake: bool,
+    pub flake_lock: Option<String>,
+    pub gate: String,
     pub id: i32,
+    pub job_counts: String,
     pub jobset_name: String,
+    pub outcome: String,
     pub project_id: i32,
     pub task_id: i32,
     pub time_created: i64,
@@ -75,7 +118,9 @@ pub struct Evaluation {
 #[diesel(table_name = evaluations)]
 pub struct NewEvaluation<'a> {
     pub actions_path: Option<&'a str>,
+    pub bisection: bool,
     pub flake: bool,
+    pub flake_lock: Option<&'a str>,
     pub jobset_name: &'a str,
     pub project_id: i32,
     pub task_id: i32,
@@ -91,9 +136,14 @@ pub struct Job {
     pub dist: bool,
     pub drv: String,
     pub evaluation_id: i32,
+    pub flaky: bool,
     pub id: i32,
+    pub max_attempts: Option<i32>,
+    pub max_silent_time: Option<i64>,
     pub name: String,
     pub out: String,
+    pub required: bool,
+    pub timeout: Option<i64>,
     pub tries: i32,
 }
 
@@ -103,8 +153,12 @@ pub struct NewJob<'a> {
     pub dist: bool,
     pub drv: &'a str,
     pub evaluation_id: i32,
+    pub max_attempts: Option<i32>,
+    pub max_silent_time: Option<i64>,
     pub name: &'a str,
     pub out: &'a str,
+    pub required: bool,
+    pub timeout: Option<i64>,
     pub tries: i32,
 }
 
@@ -144,7 +198,9 @@ pub struct NewTask {
 #[diesel(belongs_to(Task))]
 pub struct Build {
     pub drv: String,
+    pub failed_dependency: Option<String>,
     pub id: i32,
+    pub outcome: Option<String>,
     pub task_id: i32,
     pub time_created: i64,
     pub uuid: String,
@@ -164,11 +220,14 @@ pub struct NewBuild<'a> {
 #[diesel(belongs_to(Project))]
 #[diesel(belongs_to(Task))]
 pub struct Action {
+    pub exit_code: Option<i32>,
+    pub failure: Option<String>,
     pub id: i32,
     pub input: String,
     pub name: String,
     pub path: String,
     pub project_id: i32,
+    pub results: String,
     pub task_id: i32,
     pub time_created: i64,
     pub uuid: String,
@@ -186,6 +245,107 @@ pub struct NewAction<'a> {
     pub uuid: &'a str,
 }
 
+#[derive(Debug, Queryable, Clone, Identifiable, Selectable)]
+#[diesel(table_name = deliveries)]
+#[diesel(belongs_to(Action))]
+#[diesel(belongs_to(Project))]
+pub struct Delivery {
+    pub action_id: i32,
+    pub body: String,
+    pub commands: Option<String>,
+    pub headers: String,
+    pub id: i32,
+    pub outcome: Option<String>,
+    pub project_id: i32,
+    pub time_created: i64,
+    pub uuid: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = deliveries)]
+pub struct NewDelivery<'a> {
+    pub action_id: i32,
+    pub body: &'a str,
+    pub headers: &'a str,
+    pub project_id: i32,
+    pub time_created: i64,
+    pub uuid: &'a str,
+}
+
+#[derive(Debug, Queryable, Clone, Identifiable, Selectable)]
+#[diesel(table_name = quarantines)]
+#[diesel(belongs_to(Jobset))]
+pub struct Quarantine {
+    pub id: i32,
+    pub job_name: String,
+    pub jobset_id: i32,
+    pub reason: String,
+    pub time_created: i64,
+    pub time_expires: i64,
+    pub time_lifted: Option<i64>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = quarantines)]
+pub struct NewQuarantine<'a> {
+    pub job_name: &'a str,
+    pub jobset_id: i32,
+    pub reason: &'a str,
+    pub time_created: i64,
+    pub time_expires: i64,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = latest_jobs)]
+pub struct NewLatestJob<'a> {
+    pub job_id: i32,
+    pub job_name: &'a str,
+    pub jobset_id: i32,
+}
+
+#[derive(Debug, Queryable, Clone, Identifiable, Selectable)]
+#[diesel(table_name = bisections)]
+#[diesel(belongs_to(Jobset))]
+pub struct Bisection {
+    pub bad_evaluation_id: i32,
+    pub good_evaluation_id: Option<i32>,
+    pub id: i32,
+    pub job_name: String,
+    pub jobset_id: i32,
+    pub probe_evaluation_id: Option<i32>,
+    pub revisions: String,
+    pub time_created: i64,
+    pub time_finished: Option<i64>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = bisections)]
+pub struct NewBisection<'a> {
+    pub bad_evaluation_id: i32,
+    pub good_evaluation_id: Option<i32>,
+    pub job_name: &'a str,
+    pub jobset_id: i32,
+    pub revisions: &'a str,
+    pub time_created: i64,
+}
+
+#[derive(Debug, Queryable, Clone, Identifiable, Selectable)]
+#[diesel(table_name = run_actions)]
+#[diesel(belongs_to(Action))]
+#[diesel(belongs_to(Run))]
+pub struct RunAction {
+    pub action_id: i32,
+    pub id: i32,
+    pub run_id: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = run_actions)]
+pub struct NewRunAction {
+    pub action_id: i32,
+    pub run_id: i32,
+}
+
 #[derive(Debug, Queryable, Clone, Identifiable, Selectable)]
 #[diesel(table_name = runs)]
 #[diesel(belongs_to(Job))]
@@ -6,7 +6,13 @@ use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::mpsc;
 
-use std::{collections::HashMap, ffi::OsStr, process::Stdio};
+use typhon_types::responses::FlakeLock;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    process::Stdio,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
@@ -33,6 +39,8 @@ pub enum Error {
     },
     ExpectedDrvGotAttrset(Expr),
     BuildFailed,
+    NonDeterministic,
+    TimedOut,
 }
 
 impl From<serde_json::Error> for Error {
@@ -131,10 +139,43 @@ async fn handle_logs(
     }
 }
 
+/// Options of `nix build`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// Rebuild outputs that are already built, failing with
+    /// `Error::NonDeterministic` if they differ
+    pub check: bool,
+    /// Maximum duration of the build, in seconds
+    pub timeout: Option<u64>,
+    /// Maximum duration without any output from the build, in seconds
+    pub max_silent_time: Option<u64>,
+    /// Queued builds with a higher priority are started first
+    pub priority: i32,
+}
+
 /// Runs `nix build` on a derivation path
 pub async fn build(
     path: &DrvPath,
     sender: mpsc::UnboundedSender<String>,
+) -> Result<DrvOutputs, Error> {
+    realise(path, &BuildOptions::default(), sender).await
+}
+
+/// Nix exits with `100` plus a bitmask on build failures: the bit `1` is set
+/// when a build timed out and the bit `4` when a rebuild with `--check` gave
+/// different outputs
+fn failure_mask(status: std::process::ExitStatus) -> Option<i32> {
+    status
+        .code()
+        .and_then(|code| code.checked_sub(100))
+        .filter(|mask| (1..16).contains(mask))
+}
+
+/// Runs `nix build` on a derivation path, with the given options
+pub async fn realise(
+    path: &DrvPath,
+    options: &BuildOptions,
+    sender: mpsc::UnboundedSender<String>,
 ) -> Result<DrvOutputs, Error> {
     let mut child = Command::nix([
         "build",
@@ -143,6 +184,19 @@ pub async fn build(
         "--json",
         "--no-link",
     ])
+    .args(options.check.then_some("--check"))
+    .args(
+        options
+            .timeout
+            .into_iter()
+            .flat_map(|timeout| ["--timeout".to_string(), timeout.to_string()]),
+    )
+    .args(
+        options
+            .max_silent_time
+            .into_iter()
+            .flat_map(|time| ["--max-silent-time".to_string(), time.to_string()]),
+    )
     .arg(format!("{}^*", path))
     .stdin(Stdio::inherit())
     .stdout(Stdio::piped())
@@ -158,12 +212,13 @@ pub async fn build(
         .read_to_string(&mut stdout)
         .await
         .unwrap();
-    let success = child
-        .wait()
-        .await
-        .map(|status| status.success())
-        .unwrap_or(false);
-    if success {
+    let status = child.wait().await.ok();
+    match status.and_then(failure_mask) {
+        Some(mask) if mask & 4 != 0 => return Err(Error::NonDeterministic),
+        Some(mask) if mask & 1 != 0 => return Err(Error::TimedOut),
+        _ => (),
+    }
+    if status.is_some_and(|status| status.success()) {
         if let [obj] = serde_json::from_str::<Value>(stdout.as_str())
             .unwrap()
             .as_array()
@@ -304,57 +359,96 @@ pub async fn derivation(expr: Expr) -> Result<Derivation, Error> {
     }
 }
 
+fn eval_command(url: &str, path: &str, flake: bool) -> Command {
+    if flake {
+        Command::nix(["eval", "--json", &format!("{}#{}", url, path)])
+    } else {
+        Command::nix([
+            "eval",
+            "--json",
+            "--no-write-lock-file",
+            "--override-input",
+            "x",
+            url,
+            &format!("{}#{}", env!("TYPHON_FLAKE"), path),
+        ])
+    }
+}
+
 pub async fn eval(url: &str, path: &str, flake: bool) -> Result<serde_json::Value, Error> {
     Ok(serde_json::from_str(
-        &(if flake {
-            Command::nix(["eval", "--json", &format!("{}#{}", url, path)])
-        } else {
-            Command::nix([
-                "eval",
-                "--json",
-                "--no-write-lock-file",
-                "--override-input",
-                "x",
-                url,
-                &format!("{}#{}", env!("TYPHON_FLAKE"), path),
-            ])
-        })
-        .sync_stdout()
-        .await?
-        .to_string(),
+        &eval_command(url, path, flake).sync_stdout().await?,
+    )?)
+}
+
+/// Evaluates an attribute of a flake, applying a Nix function to it
+pub async fn eval_apply(
+    url: &str,
+    path: &str,
+    flake: bool,
+    apply: &str,
+) -> Result<serde_json::Value, Error> {
+    Ok(serde_json::from_str(
+        &eval_command(url, path, flake)
+            .args(["--apply", apply])
+            .sync_stdout()
+            .await?,
     )?)
 }
 
-pub type NewJobs = HashMap<String, (Derivation, bool)>;
+/// A job of an evaluation, along with the settings read from its `passthru`
+#[derive(Clone, Debug)]
+pub struct NewJob {
+    pub drv: Derivation,
+    pub dist: bool,
+    pub timeout: Option<u64>,
+    pub max_silent_time: Option<u64>,
+    pub max_attempts: Option<u32>,
+    pub required: bool,
+}
+
+pub type NewJobs = HashMap<String, NewJob>;
+
+/// The attributes of `passthru` read by Typhon, evaluated for all the jobs at
+/// once
+const PASSTHRU: &str = "builtins.mapAttrs (_: job: builtins.intersectAttrs \
+    { typhonDist = null; typhonTimeout = null; typhonMaxSilentTime = null; \
+    typhonMaxAttempts = null; typhonRequired = null; } (job.passthru or { }))";
 
 pub async fn eval_jobs(url: &str, flake: bool) -> Result<NewJobs, Error> {
-    let json = eval(url, "typhonJobs", flake).await?;
+    let json = eval_apply(url, "typhonJobs", flake, PASSTHRU).await?;
     let mut jobs: NewJobs = HashMap::new();
-    for name in json.as_object().unwrap().keys() {
+    for (name, passthru) in json.as_object().unwrap() {
+        let passthru = |attr: &str| passthru.get(attr);
         jobs.insert(
             name.clone(),
-            (
-                derivation(Expr::Flake {
+            NewJob {
+                drv: derivation(Expr::Flake {
                     flake,
                     url: url.to_string(),
                     path: format!("typhonJobs.{name}"),
                 })
                 .await?,
-                eval(
-                    url,
-                    &format!("typhonJobs.{name}.passthru.typhonDist"),
-                    flake,
-                )
-                .await
-                .map(|json| json.as_bool().unwrap_or(false))
-                .unwrap_or(false),
-            ),
+                dist: passthru("typhonDist")
+                    .and_then(|json| json.as_bool())
+                    .unwrap_or(false),
+                timeout: passthru("typhonTimeout").and_then(|json| json.as_u64()),
+                max_silent_time: passthru("typhonMaxSilentTime").and_then(|json| json.as_u64()),
+                max_attempts: passthru("typhonMaxAttempts")
+                    .and_then(|json| json.as_u64())
+                    .map(|n| n as u32),
+                required: passthru("typhonRequired")
+                    .and_then(|json| json.as_bool())
+                    .unwrap_or(false),
+            },
         );
     }
     Ok(jobs)
 }
 
-pub fn lock(url: &String) -> Result<String, Error> {
+/// Locks a flake URL, returning the locked URL along with the lock of the
+/// flake and of its inputs
+pub fn lock_with_inputs(url: &String) -> Result<(String, FlakeLock), Error> {
     use std::process::Command;
 
     let mut cmd = Command::new("nix");
@@ -379,7 +473,8 @@ pub fn lock(url: &String) -> Result<String, Error> {
         });
     }
 
-    let locked_info = &serde_json::from_str::<Value>(&stdout).unwrap()["nodes"]["x"]["locked"];
+    let lock_file = serde_json::from_str::<Value>(&stdout).unwrap();
+    let locked_info = &lock_file["nodes"]["x"]["locked"];
 
     let mut cmd = Command::new("nix");
     cmd.args([
@@ -402,7 +497,47 @@ pub fn lock(url: &String) -> Result<String, Error> {
         });
     }
 
-    Ok(stdout)
+    Ok((stdout, flake_lock(&lock_file, "x")))
+}
+
+pub fn lock(url: &String) -> Result<String, Error> {
+    Ok(lock_with_inputs(url)?.0)
+}
+
+/// The lock of a node of a lock file, with the locks of its inputs
+fn flake_lock(lock_file: &Value, node: &str) -> FlakeLock {
+    let nodes = &lock_file["nodes"];
+    let locked = &nodes[node]["locked"];
+    let inputs = nodes[node]["inputs"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, input)| {
+            let lock = match input {
+                Value::String(node) => flake_lock(lock_file, node),
+                // the input follows another one, given by its path from the root
+                Value::Array(path) => {
+                    let node = path
+                        .iter()
+                        .try_fold(lock_file["root"].as_str()?, |node, input| {
+                            nodes[node]["inputs"][input.as_str()?].as_str()
+                        })?;
+                    FlakeLock {
+                        inputs: BTreeMap::new(),
+                        ..flake_lock(lock_file, node)
+                    }
+                }
+                _ => return None,
+            };
+            Some((name.clone(), lock))
+        })
+        .collect();
+    FlakeLock {
+        rev: locked["rev"].as_str().map(String::from),
+        last_modified: locked["lastModified"].as_i64(),
+        nar_hash: locked["narHash"].as_str().map(String::from),
+        inputs,
+    }
 }
 
 pub fn dependencies(drv: &String) -> Result<Vec<String>, Error> {
@@ -1,4 +1,6 @@
 use crate::actions;
+use crate::builds;
+use crate::deliveries;
 use crate::error::Error;
 use crate::gcroots;
 use crate::jobsets;
@@ -6,6 +8,7 @@ use crate::models;
 use crate::nix;
 use crate::schema;
 use crate::tasks;
+use crate::timestamp_in;
 use crate::Conn;
 use crate::CURRENT_SYSTEM;
 use crate::POOL;
@@ -20,11 +23,15 @@ use age::secrecy::ExposeSecret;
 use diesel::prelude::*;
 use serde::Deserialize;
 use time::OffsetDateTime;
-use tokio::sync::oneshot;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 
+type CustomActions = BTreeMap<String, responses::CustomAction>;
+
+/// The locked URL, metadata and actions of a project, as found by a refresh
+type Refreshed = (String, ProjectMetadata, Option<(String, CustomActions)>);
+
 #[derive(Clone)]
 pub struct Project {
     pub refresh_task: Option<tasks::Task>,
@@ -67,6 +74,10 @@ impl Project {
     //    todo!()
     //}
 
+    pub fn custom_actions(&self) -> CustomActions {
+        serde_json::from_str(&self.project.custom_actions).unwrap_or_default()
+    }
+
     pub fn delete_jobset(&self, conn: &mut Conn, name: &String) -> Result<(), Error> {
         let jobset = jobsets::Jobset::get(
             conn,
@@ -75,10 +86,35 @@ impl Project {
                 name: name.clone(),
             },
         )?;
-        diesel::delete(schema::jobsets::table.find(&jobset.jobset.id)).execute(conn)?;
+        jobset.close(conn)?;
+        log_event(Event::ProjectUpdated(self.handle()));
+        gcroots::update(conn);
         Ok(())
     }
 
+    pub fn encrypt_secrets(&self, secrets: &serde_json::Value) -> Result<String, Error> {
+        use age::armor::{ArmoredWriter, Format};
+        use std::io::Write;
+
+        let recipient = age::x25519::Identity::from_str(&self.project.key)
+            .map_err(|_| actions::Error::InvalidKey)?
+            .to_public();
+        let encryptor = age::Encryptor::with_recipients(vec![Box::new(recipient)])
+            .ok_or(actions::Error::Unexpected)?;
+        let mut encrypted = vec![];
+        let armor = ArmoredWriter::wrap_output(&mut encrypted, Format::AsciiArmor)
+            .map_err(|_| actions::Error::Unexpected)?;
+        let mut writer = encryptor
+            .wrap_output(armor)
+            .map_err(|_| actions::Error::Unexpected)?;
+        writer
+            .write_all(secrets.to_string().as_bytes())
+            .and_then(|()| writer.finish())
+            .and_then(|armor| armor.finish())
+            .map_err(|_| actions::Error::Unexpected)?;
+        Ok(String::from_utf8(encrypted).map_err(|_| actions::Error::Unexpected)?)
+    }
+
     pub fn get(conn: &mut Conn, handle: &handles::Project) -> Result<Self, Error> {
         let (project, task): (models::Project, Option<models::Task>) = schema::projects::table
             .left_join(schema::tasks::table)
@@ -97,28 +133,69 @@ impl Project {
     }
 
     pub fn info(&self, conn: &mut Conn) -> Result<responses::ProjectInfo, Error> {
-        let jobsets_names = schema::jobsets::table
+        let mut jobsets_names = Vec::new();
+        let mut closed_jobsets_names = Vec::new();
+        let mut hidden_jobsets_names = Vec::new();
+        for jobset in schema::jobsets::table
             .filter(schema::jobsets::project_id.eq(&self.project.id))
+            .order((schema::jobsets::priority.desc(), schema::jobsets::name))
             .load::<models::Jobset>(conn)?
-            .iter()
-            .map(|jobset| jobset.name.clone())
-            .collect();
+        {
+            if jobset.time_closed.is_some() {
+                closed_jobsets_names.push(jobset.name);
+            } else if jobset.hidden {
+                hidden_jobsets_names.push(jobset.name);
+            } else {
+                jobsets_names.push(jobset.name);
+            }
+        }
         let public_key = age::x25519::Identity::from_str(&self.project.key)
             .map_err(|_| Error::Todo)?
             .to_public()
             .to_string();
+        let previous_key_expiry = self
+            .project
+            .previous_key_expiry
+            .and_then(|expiry| OffsetDateTime::from_unix_timestamp(expiry).ok())
+            .filter(|expiry| *expiry > OffsetDateTime::now_utc());
+        let previous_public_key = match (&self.project.previous_key, previous_key_expiry) {
+            (Some(key), Some(_)) => Some(
+                age::x25519::Identity::from_str(key)
+                    .map_err(|_| Error::Todo)?
+                    .to_public()
+                    .to_string(),
+            ),
+            _ => None,
+        };
         Ok(responses::ProjectInfo {
             handle: self.handle(),
             actions_path: self.project.actions_path.clone(),
+            custom_actions: self.custom_actions(),
             flake: self.project.flake,
+            forge: self.forge().map(|forge| responses::ForgeInfo {
+                kind: forge.kind,
+                api: forge.api,
+                repository: forge.repository,
+            }),
+            has_webhook_secret: self.project.webhook_secret.is_some(),
             jobsets: jobsets_names,
+            closed_jobsets: closed_jobsets_names,
+            hidden_jobsets: hidden_jobsets_names,
             last_refresh: self.refresh_task.clone().map(|task| task.status()),
+            last_refresh_failure: self
+                .project
+                .last_refresh_failure
+                .as_ref()
+                .and_then(|failure| serde_json::from_str(failure).ok()),
             metadata: ProjectMetadata {
                 title: self.project.title.clone(),
                 description: self.project.description.clone(),
                 homepage: self.project.homepage.clone(),
             },
+            previous_key_expiry: previous_public_key.as_ref().and(previous_key_expiry),
+            previous_public_key,
             public_key,
+            sandbox: self.sandbox(),
             url: self.project.url.clone(),
             url_locked: self.project.url_locked.clone(),
         })
@@ -167,18 +244,46 @@ impl Project {
         name: &String,
         decl: &JobsetDecl,
     ) -> Result<(), Error> {
-        let new_jobset = models::NewJobset {
-            flake: decl.flake,
-            name,
-            project_id: self.project.id,
-            url: &decl.url,
-        };
-        diesel::insert_into(schema::jobsets::table)
-            .values(&new_jobset)
-            .execute(conn)?;
+        jobsets::Jobset::declare(conn, &self.project, name, decl)?;
+        log_event(Event::ProjectUpdated(self.handle()));
         Ok(())
     }
 
+    /// The latest builds of the project found to be non-deterministic, one
+    /// per derivation
+    pub fn non_deterministic_builds(
+        &self,
+        conn: &mut Conn,
+    ) -> Result<Vec<responses::BuildInfo>, Error> {
+        let mut drvs = HashSet::new();
+        Ok(schema::builds::table
+            .inner_join(schema::tasks::table)
+            .filter(
+                schema::builds::outcome
+                    .eq(serde_json::to_string(&responses::BuildOutcome::NonDeterministic).unwrap()),
+            )
+            .filter(
+                schema::builds::id.nullable().eq_any(
+                    schema::runs::table
+                        .inner_join(schema::jobs::table.inner_join(schema::evaluations::table))
+                        .filter(schema::evaluations::project_id.eq(self.project.id))
+                        .select(schema::runs::build_id),
+                ),
+            )
+            .order(schema::builds::time_created.desc())
+            .load::<(models::Build, models::Task)>(conn)?
+            .into_iter()
+            .filter(|(build, _)| drvs.insert(build.drv.clone()))
+            .map(|(build, task)| {
+                builds::Build {
+                    build,
+                    task: tasks::Task { task },
+                }
+                .info()
+            })
+            .collect())
+    }
+
     pub fn refresh(&self, conn: &mut Conn) -> Result<(), Error> {
         #[derive(Deserialize)]
         struct TyphonProject {
@@ -188,6 +293,7 @@ impl Project {
         }
 
         let run = {
+            let project = self.project.clone();
             let url = self.project.url.clone();
             let flake = self.project.flake;
             move |sender| async move {
@@ -203,8 +309,10 @@ impl Project {
                 let actions_path = if let Some(x) = actions {
                     let drv = nix::derivation(nix::Expr::Path(x.clone())).await?;
                     // FIXME: this should spawn a build
-                    Some(nix::build(&drv.path, sender).await?["out"].clone())
-                    // TODO: check public key used to encrypt secrets
+                    let path = nix::build(&drv.path, sender).await?["out"].clone();
+                    actions::secrets(&project, &path)?;
+                    let custom_actions = actions::custom_actions(&path)?;
+                    Some((path, custom_actions))
                 } else {
                     None
                 };
@@ -215,12 +323,12 @@ impl Project {
 
         let finish = {
             let self_ = self.clone();
-            move |res: Option<Result<(String, ProjectMetadata, Option<String>), Error>>| {
+            move |res: Option<Result<Refreshed, Error>>| {
                 let status = match res {
                     Some(Ok(x)) => self_.finish_refresh(x),
                     Some(Err(e)) => {
                         tracing::warn!("refresh error for project {}: {}", self_.handle(), e);
-                        Ok(TaskStatusKind::Failure)
+                        self_.fail_refresh(e)
                     }
                     None => Ok(TaskStatusKind::Canceled),
                 };
@@ -233,7 +341,10 @@ impl Project {
 
         let task = tasks::Task::new(conn)?;
         diesel::update(&self.project)
-            .set(schema::projects::last_refresh_task_id.eq(task.task.id))
+            .set((
+                schema::projects::last_refresh_failure.eq(None::<String>),
+                schema::projects::last_refresh_task_id.eq(task.task.id),
+            ))
             .execute(conn)?;
 
         log_event(Event::ProjectUpdated(self.handle()));
@@ -243,6 +354,75 @@ impl Project {
         Ok(())
     }
 
+    pub fn rotate_key(&self, conn: &mut Conn, grace_period: u64) -> Result<(), Error> {
+        let key = age::x25519::Identity::generate()
+            .to_string()
+            .expose_secret()
+            .clone();
+        // a key still in its grace period is dropped, only the current one
+        // remains valid alongside the new one
+        let expiry = timestamp_in(grace_period)?;
+        diesel::update(&self.project)
+            .set((
+                schema::projects::key.eq(&key),
+                schema::projects::previous_key.eq(&self.project.key),
+                schema::projects::previous_key_expiry.eq(expiry),
+            ))
+            .execute(conn)?;
+        log_event(Event::ProjectUpdated(self.handle()));
+        Ok(())
+    }
+
+    pub fn run_action(
+        &self,
+        conn: &mut Conn,
+        name: &String,
+        input: &serde_json::Value,
+    ) -> Result<handles::Action, Error> {
+        let custom_action = self
+            .custom_actions()
+            .remove(name)
+            .ok_or(Error::CustomActionNotFound(name.clone()))?;
+        let schema = jsonschema::JSONSchema::compile(&custom_action.schema)
+            .map_err(|_| Error::ActionError(actions::Error::InvalidCustomActions))?;
+        if let Err(errors) = schema.validate(input) {
+            let errors: Vec<String> = errors.map(|e| e.to_string()).collect();
+            return Err(Error::InvalidActionInput(errors.join(", ")));
+        }
+
+        let action = self.new_action(
+            conn,
+            &self
+                .project
+                .actions_path
+                .clone()
+                .unwrap_or("/dev/null".to_string()),
+            name,
+            input,
+        )?;
+        let handle = action.handle();
+
+        let finish = |output: Option<String>| match output {
+            Some(_) => TaskStatusKind::Success,
+            None => TaskStatusKind::Failure,
+        };
+
+        action.spawn(conn, finish)?;
+
+        Ok(handle)
+    }
+
+    pub fn forge(&self) -> Option<typhon_types::requests::ForgeSettings> {
+        self.project
+            .forge
+            .as_ref()
+            .and_then(|forge| serde_json::from_str(forge).ok())
+    }
+
+    pub fn sandbox(&self) -> typhon_types::requests::SandboxPolicy {
+        serde_json::from_str(&self.project.sandbox).unwrap_or_default()
+    }
+
     pub fn set_decl(
         &self,
         conn: &mut Conn,
@@ -258,6 +438,46 @@ impl Project {
         Ok(())
     }
 
+    pub fn set_sandbox(
+        &self,
+        conn: &mut Conn,
+        policy: &typhon_types::requests::SandboxPolicy,
+    ) -> Result<(), Error> {
+        diesel::update(&self.project)
+            .set(schema::projects::sandbox.eq(serde_json::to_string(policy).unwrap()))
+            .execute(conn)?;
+        log_event(Event::ProjectUpdated(self.handle()));
+        Ok(())
+    }
+
+    pub fn set_forge(
+        &self,
+        conn: &mut Conn,
+        forge: &Option<typhon_types::requests::ForgeSettings>,
+    ) -> Result<(), Error> {
+        diesel::update(&self.project)
+            .set(
+                schema::projects::forge.eq(forge
+                    .as_ref()
+                    .map(|forge| serde_json::to_string(forge).unwrap())),
+            )
+            .execute(conn)?;
+        log_event(Event::ProjectUpdated(self.handle()));
+        Ok(())
+    }
+
+    pub fn set_webhook_secret(
+        &self,
+        conn: &mut Conn,
+        secret: &Option<String>,
+    ) -> Result<(), Error> {
+        diesel::update(&self.project)
+            .set(schema::projects::webhook_secret.eq(secret))
+            .execute(conn)?;
+        log_event(Event::ProjectUpdated(self.handle()));
+        Ok(())
+    }
+
     pub fn update_jobsets(&self, conn: &mut Conn) -> Result<(), Error> {
         // run action `jobsets`
         let action = self.new_action(
@@ -301,63 +521,48 @@ impl Project {
         Ok(())
     }
 
-    pub fn webhook(&self, conn: &mut Conn, input: actions::webhooks::Input) -> Result<(), Error> {
-        use crate::handle_request_aux;
-        use crate::User;
-
-        let (sender, receiver) = oneshot::channel();
-
-        let input = serde_json::to_value(input).unwrap();
-
-        let action = self.new_action(
-            conn,
-            &self
-                .project
-                .actions_path
-                .clone() // FIXME? why do we need this clone?
-                .unwrap_or("/dev/null".to_string()),
-            &"webhook".to_string(),
-            &input,
-        )?;
+    pub fn webhook(
+        &self,
+        conn: &mut Conn,
+        input: actions::webhooks::Input,
+    ) -> Result<handles::Delivery, Error> {
+        Ok(deliveries::Delivery::new(conn, self, input)?.handle())
+    }
 
-        let finish = move |output: Option<String>| match output {
-            Some(output) => match serde_json::from_str::<actions::webhooks::Output>(&output) {
-                Ok(cmds) => {
-                    let _ = sender.send(Ok(cmds));
-                    TaskStatusKind::Success
-                }
-                Err(_) => {
-                    let _ = sender.send(Err(Some(output)));
-                    TaskStatusKind::Failure
-                }
-            },
-            None => {
-                let _ = sender.send(Err(None));
-                TaskStatusKind::Failure
+    fn fail_refresh(&self, e: Error) -> Result<TaskStatusKind, Error> {
+        use responses::RefreshFailure;
+        let failure = match e {
+            Error::BadProjectDecl => RefreshFailure::BadDecl,
+            Error::NixError(_) => RefreshFailure::Nix,
+            Error::ActionError(actions::Error::WrongRecipient) => {
+                RefreshFailure::SecretsWrongRecipient
             }
+            Error::ActionError(actions::Error::InvalidSecrets) => RefreshFailure::InvalidSecrets,
+            Error::ActionError(actions::Error::InvalidCustomActions) => {
+                RefreshFailure::InvalidCustomActions
+            }
+            _ => RefreshFailure::Internal,
         };
-
-        action.spawn(conn, finish)?;
-
-        let cmds = receiver.blocking_recv().map_err(|_| Error::Todo)?;
-        let cmds = cmds.map_err(|output| Error::WebhookFailure(output))?;
-        for cmd in cmds {
-            let req = cmd.lift(self.handle().clone());
-            tracing::trace!("handling request {} from webhook", req);
-            let _ = handle_request_aux(conn, &User::Admin, &req)?;
-        }
-
-        Ok(())
+        let mut conn = POOL.get().unwrap();
+        diesel::update(&self.project)
+            .set(
+                schema::projects::last_refresh_failure.eq(serde_json::to_string(&failure).unwrap()),
+            )
+            .execute(&mut conn)?;
+        Ok(TaskStatusKind::Failure)
     }
 
     fn finish_refresh(
         &self,
-        (url_locked, meta, actions_path): (String, ProjectMetadata, Option<String>),
+        (url_locked, meta, actions): Refreshed,
     ) -> Result<TaskStatusKind, Error> {
+        let (actions_path, custom_actions) = actions.unzip();
         let mut conn = POOL.get().unwrap();
         diesel::update(&self.project)
             .set((
                 schema::projects::actions_path.eq(actions_path),
+                schema::projects::custom_actions
+                    .eq(serde_json::to_string(&custom_actions.unwrap_or_default()).unwrap()),
                 schema::projects::description.eq(meta.description),
                 schema::projects::homepage.eq(meta.homepage),
                 schema::projects::title.eq(meta.title),
@@ -372,6 +577,8 @@ impl Project {
         &self,
         decls: HashMap<String, typhon_types::requests::JobsetDecl>,
     ) -> Result<TaskStatusKind, Error> {
+        use typhon_types::requests::JobsetKind;
+
         let mut conn = POOL.get().unwrap();
         let mut current_jobsets: Vec<jobsets::Jobset> = schema::jobsets::table
             .filter(schema::jobsets::project_id.eq(&self.project.id))
@@ -383,31 +590,29 @@ impl Project {
             })
             .collect();
 
-        // delete obsolete jobsets
+        // close obsolete branch jobsets, pull request jobsets are managed by
+        // the webhook. Expired jobsets stay closed even if still declared.
         let mut set = std::collections::HashSet::<String>::new();
         for jobset in current_jobsets.drain(..) {
-            if decls
-                .get(&jobset.jobset.name)
-                .is_some_and(|decl| *decl == jobset.decl())
-            {
-                set.insert(jobset.jobset.name);
-            } else {
-                jobset.delete(&mut conn)?;
+            let closed = jobset.jobset.time_closed.is_some();
+            match decls.get(&jobset.jobset.name) {
+                Some(_) if jobset.expired() => {
+                    set.insert(jobset.jobset.name);
+                }
+                Some(decl) if !closed && *decl == jobset.decl() => {
+                    set.insert(jobset.jobset.name);
+                }
+                Some(_) => (),
+                None if closed || jobset.kind() == JobsetKind::PullRequest => (),
+                None => jobset.close(&mut conn)?,
             }
         }
 
-        // create new jobsets
+        // create new jobsets, update the others and reopen those closed for
+        // not being declared
         for (name, decl) in decls.iter() {
             if !set.contains(name) {
-                let new_jobset = models::NewJobset {
-                    flake: decl.flake,
-                    name,
-                    project_id: self.project.id,
-                    url: &decl.url,
-                };
-                diesel::insert_into(schema::jobsets::table)
-                    .values(&new_jobset)
-                    .execute(&mut conn)?;
+                jobsets::Jobset::declare(&mut conn, &self.project, name, decl)?;
             }
         }
 
@@ -10,6 +10,7 @@ use crate::tasks;
 use crate::Conn;
 use crate::POOL;
 use crate::RUNS;
+use crate::RUNTIME;
 
 use typhon_types::data::TaskStatusKind;
 use typhon_types::*;
@@ -24,6 +25,7 @@ pub struct Run {
     pub begin: Option<actions::Action>,
     pub end: Option<actions::Action>,
     pub build: Option<builds::Build>,
+    pub reruns: Vec<actions::Action>,
     pub run: models::Run,
     pub job: models::Job,
     pub evaluation: models::Evaluation,
@@ -111,6 +113,19 @@ impl Run {
             )>(conn)
             .optional()?
             .ok_or(Error::RunNotFound(handle.clone()))?;
+        let reruns = schema::run_actions::table
+            .inner_join(schema::actions::table.inner_join(schema::tasks::table))
+            .filter(schema::run_actions::run_id.eq(run.id))
+            .order(schema::run_actions::id.asc())
+            .select((schema::actions::all_columns, schema::tasks::all_columns))
+            .load::<(models::Action, models::Task)>(conn)?
+            .into_iter()
+            .map(|(action, task)| actions::Action {
+                project: project.clone(),
+                action,
+                task: tasks::Task { task },
+            })
+            .collect();
         Ok(Run {
             begin: begin.map(|(action, task)| actions::Action {
                 project: project.clone(),
@@ -126,6 +141,7 @@ impl Run {
                 action,
                 task: tasks::Task { task },
             }),
+            reruns,
             run,
             job,
             evaluation,
@@ -141,6 +157,25 @@ impl Run {
         ))
     }
 
+    pub fn jobset_handle(&self) -> handles::Jobset {
+        handles::jobset((
+            self.project.name.clone(),
+            self.evaluation.jobset_name.clone(),
+        ))
+    }
+
+    /// Logs that the run was created, along with the jobs of its jobset
+    pub fn log_new(&self) {
+        log_event(Event::RunNew(self.handle()));
+        log_event(Event::JobsUpdated(self.jobset_handle()));
+    }
+
+    /// Logs that the run was updated, along with the jobs of its jobset
+    fn log_updated(&self) {
+        log_event(Event::RunUpdated(self.handle()));
+        log_event(Event::JobsUpdated(self.jobset_handle()));
+    }
+
     pub fn info(&self) -> responses::RunInfo {
         use crate::evaluations::ExtraRunInfo;
         let Run {
@@ -150,14 +185,51 @@ impl Run {
             end,
             ..
         } = self.clone();
-        responses::RunInfo::new(
-            &handles::project(self.project.name.clone()),
-            &self.handle().job,
-            run,
-            begin.map(|actions::Action { action, task, .. }| (action, task.task)),
-            build.map(|builds::Build { build, task }| (build, task.task)),
-            end.map(|actions::Action { action, task, .. }| (action, task.task)),
-        )
+        responses::RunInfo {
+            reruns: self.reruns.iter().map(actions::Action::info).collect(),
+            ..responses::RunInfo::new(
+                &handles::project(self.project.name.clone()),
+                &self.handle().job,
+                run,
+                begin.map(|actions::Action { action, task, .. }| (action, task.task)),
+                build.map(|builds::Build { build, task }| (build, task.task)),
+                end.map(|actions::Action { action, task, .. }| (action, task.task)),
+            )
+        }
+    }
+
+    /// Spawns the `begin` action again, for a run whose build succeeded
+    pub fn rerun_begin(&self, conn: &mut Conn) -> Result<(), Error> {
+        self.rerun_action(conn, "begin", TaskStatusKind::Pending)
+    }
+
+    /// Spawns the `end` action again, for a run whose build succeeded
+    pub fn rerun_end(&self, conn: &mut Conn) -> Result<(), Error> {
+        self.rerun_action(conn, "end", TaskStatusKind::Success)
+    }
+
+    fn rerun_action(
+        &self,
+        conn: &mut Conn,
+        name: &str,
+        status: TaskStatusKind,
+    ) -> Result<(), Error> {
+        let successful = self
+            .build
+            .as_ref()
+            .is_some_and(|build| build.task.status_kind() == TaskStatusKind::Success);
+        if !successful {
+            return Err(Error::RunNotSuccessful(self.handle()));
+        }
+        let action = self.spawn_action(conn, name, status)?;
+        diesel::insert_into(schema::run_actions::table)
+            .values(&models::NewRunAction {
+                action_id: action.action.id,
+                run_id: self.run.id,
+            })
+            .execute(conn)?;
+        self.log_updated();
+        Ok(())
     }
 
     pub fn run(&self, conn: &mut Conn) -> Result<(), Error> {
@@ -166,8 +238,23 @@ impl Run {
         use crate::TASKS;
 
         // run the build
+        let (checked_jobs, priority) = schema::jobsets::table
+            .filter(schema::jobsets::project_id.eq(self.project.id))
+            .filter(schema::jobsets::name.eq(&self.evaluation.jobset_name))
+            .select((schema::jobsets::checked_jobs, schema::jobsets::priority))
+            .first::<(Option<String>, i32)>(conn)
+            .optional()?
+            .unwrap_or_default();
+        let check = checked_jobs
+            .is_some_and(|pattern| crate::evaluations::matches_pattern(&pattern, &self.job.name));
+        let options = nix::BuildOptions {
+            check,
+            timeout: self.job.timeout.map(|timeout| timeout as u64),
+            max_silent_time: self.job.max_silent_time.map(|time| time as u64),
+            priority,
+        };
         let drv = nix::DrvPath::new(&self.job.drv);
-        let build_handle = BUILDS.run(drv);
+        let build_handle = BUILDS.run(drv, options);
 
         // run the 'begin' action
         let action_begin = self.spawn_action(conn, "begin", TaskStatusKind::Pending)?;
@@ -178,17 +265,12 @@ impl Run {
                 schema::runs::build_id.eq(build_handle.id),
             ))
             .execute(conn)?;
-        log_event(Event::RunUpdated(self.handle()));
+        self.log_updated();
 
         // a waiter task
         let run_run = async move {
             TASKS.wait(&action_begin.task.task.id).await;
-            let res = build_handle.wait().await;
-            match res {
-                Some(Some(())) => TaskStatusKind::Success,
-                Some(None) => TaskStatusKind::Failure,
-                None => TaskStatusKind::Canceled,
-            }
+            build_handle.wait().await
         };
 
         // run the 'end' action
@@ -201,7 +283,17 @@ impl Run {
                     diesel::update(&self_.run)
                         .set((schema::runs::end_id.eq(action_end.action.id),))
                         .execute(&mut conn)?;
-                    log_event(Event::RunUpdated(self_.handle()));
+                    self_.log_updated();
+                    let end_id = action_end.task.task.id;
+                    let handle = self_.handle();
+                    let jobset = self_.jobset_handle();
+                    RUNTIME.spawn(async move {
+                        TASKS.wait(&end_id).await;
+                        // the status of the run is now known
+                        log_event(Event::RunUpdated(handle.clone()));
+                        log_event(Event::JobsUpdated(jobset));
+                        retry(handle).await;
+                    });
                 }
                 Ok::<_, Error>(())
             };
@@ -216,7 +308,74 @@ impl Run {
         Ok(())
     }
 
-    fn mk_input(&self, status: TaskStatusKind) -> Result<serde_json::Value, Error> {
+    /// Once the run is finished, flags its job as flaky if it passed after a
+    /// failed run, or returns the delay before retrying it if the retry
+    /// policy applies
+    fn retry_delay(&self, conn: &mut Conn) -> Result<Option<u64>, Error> {
+        use crate::jobsets;
+
+        let status = TaskStatusKind::from(&responses::TaskStatus::from(&self.info()));
+        let num = self.run.num as u32;
+        if status == TaskStatusKind::Success {
+            if num > 1 {
+                let previous = handles::run((
+                    Uuid::from_str(&self.evaluation.uuid).unwrap(),
+                    self.job.name.clone(),
+                    num - 1,
+                ));
+                let previous = Run::get(conn, &previous)?;
+                let previous = TaskStatusKind::from(&responses::TaskStatus::from(&previous.info()));
+                if previous.is_failure() && !self.job.flaky {
+                    diesel::update(&self.job)
+                        .set(schema::jobs::flaky.eq(true))
+                        .execute(conn)?;
+                    self.log_updated();
+                }
+            }
+            return Ok(None);
+        }
+        let jobset = jobsets::Jobset::get(conn, &self.jobset_handle())?;
+        let policy = match (jobset.retry(), self.job.max_attempts) {
+            (policy, Some(max_attempts)) => requests::RetryPolicy {
+                max_attempts: max_attempts as u32,
+                ..policy.unwrap_or_default()
+            },
+            (Some(policy), None) => policy,
+            (None, None) => return Ok(None),
+        };
+        if !self.retryable(conn, &jobset)? || !policy.applies(num, status) {
+            return Ok(None);
+        }
+        Ok(Some(policy.delay(num)))
+    }
+
+    /// Whether the run can still be retried: no more recent run of its job
+    /// was started, and its jobset is neither closed nor disabled
+    fn retryable(&self, conn: &mut Conn, jobset: &crate::jobsets::Jobset) -> Result<bool, Error> {
+        let tries = schema::jobs::table
+            .find(self.job.id)
+            .select(schema::jobs::tries)
+            .first::<i32>(conn)?;
+        Ok(tries == self.run.num && jobset.jobset.time_closed.is_none() && jobset.jobset.enabled)
+    }
+
+    /// Whether the job of the run is quarantined in its jobset
+    pub fn is_quarantined(&self, conn: &mut Conn) -> Result<bool, Error> {
+        Ok(
+            crate::jobsets::active_quarantines(
+                conn,
+                self.project.id,
+                &self.evaluation.jobset_name,
+            )?
+            .contains_key(&self.job.name),
+        )
+    }
+
+    fn mk_input(
+        &self,
+        conn: &mut Conn,
+        status: TaskStatusKind,
+    ) -> Result<serde_json::Value, Error> {
         Ok(serde_json::json!({
             "drv": self.job.drv,
             "evaluation": self.evaluation.uuid,
@@ -225,6 +384,7 @@ impl Run {
             "jobset": self.evaluation.jobset_name,
             "out": self.job.out,
             "project": self.project.name,
+            "quarantined": self.is_quarantined(conn)?,
             "status": status.to_string(),
             "url": self.evaluation.url,
         }))
@@ -243,15 +403,18 @@ impl Run {
             project: self.project.clone(),
         };
 
-        let input = self.mk_input(status)?;
+        let input = self.mk_input(conn, status)?;
+
+        // evaluations of past revisions, made for bisections, must not deploy
+        // or report anything, so their actions are skipped
+        let actions_path = match self.evaluation.bisection {
+            true => None,
+            false => self.evaluation.actions_path.clone(),
+        };
 
         let action = project.new_action(
             conn,
-            &self
-                .clone() // FIXME: why do we need this clone?
-                .evaluation
-                .actions_path
-                .unwrap_or("/dev/null".to_string()),
+            &actions_path.unwrap_or("/dev/null".to_string()),
             &name.to_string(),
             &input,
         )?;
@@ -266,3 +429,45 @@ impl Run {
         Ok(action)
     }
 }
+
+/// Retries a finished run according to the retry policy of its job. Retries
+/// waiting for their delay are not persisted, and are lost on restart.
+async fn retry(handle: handles::Run) {
+    use crate::jobs;
+    use crate::jobsets;
+
+    let delay = {
+        let handle = handle.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = POOL.get().unwrap();
+            Run::get(&mut conn, &handle)?.retry_delay(&mut conn)
+        })
+        .await
+    };
+    let delay = match delay {
+        Ok(Ok(Some(delay))) => delay,
+        Ok(Ok(None)) => return,
+        Ok(Err(e)) => {
+            tracing::error!("failed to retry run {}: {}", handle, e);
+            return;
+        }
+        Err(_) => return,
+    };
+    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+    let res = tokio::task::spawn_blocking(move || {
+        let mut conn = POOL.get().unwrap();
+        // the job may have been rerun or its jobset closed during the delay
+        let run = Run::get(&mut conn, &handle)?;
+        let jobset = jobsets::Jobset::get(&mut conn, &run.jobset_handle())?;
+        if !run.retryable(&mut conn, &jobset)? {
+            return Ok(());
+        }
+        tracing::info!("retrying run {}", handle);
+        let run = jobs::Job::get(&mut conn, &handle.job)?.rerun(&mut conn)?;
+        run.run(&mut conn)
+    })
+    .await;
+    if let Ok(Err(e)) = res {
+        tracing::error!("failed to retry run: {}", e);
+    }
+}
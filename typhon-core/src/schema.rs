@@ -2,33 +2,73 @@
 
 diesel::table! {
     actions (id) {
+        exit_code -> Nullable<Integer>,
+        failure -> Nullable<Text>,
         id -> Integer,
         input -> Text,
         name -> Text,
         path -> Text,
         project_id -> Integer,
+        results -> Text,
         task_id -> Integer,
         time_created -> BigInt,
         uuid -> Text,
     }
 }
 
+diesel::table! {
+    bisections (id) {
+        bad_evaluation_id -> Integer,
+        good_evaluation_id -> Nullable<Integer>,
+        id -> Integer,
+        job_name -> Text,
+        jobset_id -> Integer,
+        probe_evaluation_id -> Nullable<Integer>,
+        revisions -> Text,
+        time_created -> BigInt,
+        time_finished -> Nullable<BigInt>,
+    }
+}
+
 diesel::table! {
     builds (id) {
         drv -> Text,
+        failed_dependency -> Nullable<Text>,
         id -> Integer,
+        outcome -> Nullable<Text>,
         task_id -> Integer,
         time_created -> BigInt,
         uuid -> Text,
     }
 }
 
+diesel::table! {
+    deliveries (id) {
+        action_id -> Integer,
+        body -> Text,
+        commands -> Nullable<Text>,
+        headers -> Text,
+        id -> Integer,
+        outcome -> Nullable<Text>,
+        project_id -> Integer,
+        time_created -> BigInt,
+        uuid -> Text,
+    }
+}
+
 diesel::table! {
     evaluations (id) {
         actions_path -> Nullable<Text>,
+        bisection -> Bool,
+        commit_author -> Nullable<Text>,
+        commit_subject -> Nullable<Text>,
         flake -> Bool,
+        flake_lock -> Nullable<Text>,
+        gate -> Text,
         id -> Integer,
+        job_counts -> Text,
         jobset_name -> Text,
+        outcome -> Text,
         project_id -> Integer,
         task_id -> Integer,
         time_created -> BigInt,
@@ -42,23 +82,49 @@ diesel::table! {
         dist -> Bool,
         drv -> Text,
         evaluation_id -> Integer,
+        flaky -> Bool,
         id -> Integer,
+        max_attempts -> Nullable<Integer>,
+        max_silent_time -> Nullable<BigInt>,
         name -> Text,
         out -> Text,
+        required -> Bool,
+        timeout -> Nullable<BigInt>,
         tries -> Integer,
     }
 }
 
 diesel::table! {
     jobsets (id) {
+        checked_jobs -> Nullable<Text>,
+        description -> Text,
+        enabled -> Bool,
+        expiry -> Nullable<BigInt>,
         flake -> Bool,
+        hidden -> Bool,
         id -> Integer,
+        keep -> Nullable<Integer>,
+        kind -> Text,
         name -> Text,
+        priority -> Integer,
         project_id -> Integer,
+        required_jobs -> Nullable<Text>,
+        retry -> Nullable<Text>,
+        time_active -> BigInt,
+        time_closed -> Nullable<BigInt>,
         url -> Text,
     }
 }
 
+diesel::table! {
+    latest_jobs (id) {
+        id -> Integer,
+        job_id -> Integer,
+        job_name -> Text,
+        jobset_id -> Integer,
+    }
+}
+
 diesel::table! {
     logs (id) {
         id -> Integer,
@@ -69,16 +135,43 @@ diesel::table! {
 diesel::table! {
     projects (id) {
         actions_path -> Nullable<Text>,
+        custom_actions -> Text,
         description -> Text,
         flake -> Bool,
+        forge -> Nullable<Text>,
         homepage -> Text,
         id -> Integer,
         key -> Text,
+        last_refresh_failure -> Nullable<Text>,
         last_refresh_task_id -> Nullable<Integer>,
         name -> Text,
+        previous_key -> Nullable<Text>,
+        previous_key_expiry -> Nullable<BigInt>,
+        sandbox -> Text,
         title -> Text,
         url -> Text,
         url_locked -> Text,
+        webhook_secret -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    quarantines (id) {
+        id -> Integer,
+        job_name -> Text,
+        jobset_id -> Integer,
+        reason -> Text,
+        time_created -> BigInt,
+        time_expires -> BigInt,
+        time_lifted -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    run_actions (id) {
+        action_id -> Integer,
+        id -> Integer,
+        run_id -> Integer,
     }
 }
 
@@ -106,24 +199,37 @@ diesel::table! {
 
 diesel::joinable!(actions -> projects (project_id));
 diesel::joinable!(actions -> tasks (task_id));
+diesel::joinable!(bisections -> jobsets (jobset_id));
 diesel::joinable!(builds -> tasks (task_id));
+diesel::joinable!(deliveries -> actions (action_id));
+diesel::joinable!(deliveries -> projects (project_id));
 diesel::joinable!(evaluations -> projects (project_id));
 diesel::joinable!(evaluations -> tasks (task_id));
 diesel::joinable!(jobs -> evaluations (evaluation_id));
 diesel::joinable!(jobsets -> projects (project_id));
+diesel::joinable!(latest_jobs -> jobs (job_id));
+diesel::joinable!(latest_jobs -> jobsets (jobset_id));
 diesel::joinable!(projects -> tasks (last_refresh_task_id));
+diesel::joinable!(quarantines -> jobsets (jobset_id));
+diesel::joinable!(run_actions -> actions (action_id));
+diesel::joinable!(run_actions -> runs (run_id));
 diesel::joinable!(runs -> builds (build_id));
 diesel::joinable!(runs -> jobs (job_id));
 diesel::joinable!(tasks -> logs (log_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     actions,
+    bisections,
     builds,
+    deliveries,
     evaluations,
     jobs,
     jobsets,
+    latest_jobs,
     logs,
     projects,
+    quarantines,
+    run_actions,
     runs,
     tasks,
 );
@@ -64,8 +64,10 @@ pub fn search(
                 .select(schema::evaluations::uuid)
                 .order(schema::evaluations::time_created.desc()),
             filters(s): [
+                s.bisection.map(|x| schema::evaluations::bisection.eq(x)),
                 s.project_name.map(|x| schema::projects::name.eq(x)),
                 s.jobset_name.map(|x| schema::evaluations::jobset_name.eq(x)),
+                s.outcome.map(|x| schema::evaluations::outcome.eq(serde_json::to_string(&x).unwrap())),
                 s.status.map(|x| schema::tasks::status.eq(i32::from(x))),
             ],
             |uuid: String| handles::evaluation(Uuid::from_str(&uuid).unwrap()),
@@ -96,6 +98,17 @@ pub fn search(
             |uuid: String| handles::action(Uuid::from_str(&uuid).unwrap()),
             Results::Actions
         ),
+        Kind::Deliveries(s) => run!(
+            schema::deliveries::table
+                .inner_join(schema::projects::table)
+                .select(schema::deliveries::uuid)
+                .order(schema::deliveries::time_created.desc()),
+            filters(s): [
+                s.project_name.map(|x| schema::projects::name.eq(x)),
+            ],
+            |uuid: String| handles::delivery(Uuid::from_str(&uuid).unwrap()),
+            Results::Deliveries
+        ),
         Kind::Runs(s) => run!(
             schema::runs::table
                 .inner_join(
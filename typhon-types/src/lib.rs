@@ -52,6 +52,11 @@ pub mod handles {
         pub uuid: Uuid,
     }
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+    #[serde(transparent)]
+    pub struct Delivery {
+        pub uuid: Uuid,
+    }
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
     pub enum Log {
         Action(Action),
         Build(Build),
@@ -157,6 +162,12 @@ pub mod handles {
             vec![x.uuid.to_string()]
         }
     }
+    impl_display!(Delivery);
+    impl From<Delivery> for Vec<String> {
+        fn from(x: Delivery) -> Self {
+            vec![x.uuid.to_string()]
+        }
+    }
     impl_display!(Log);
     impl From<Log> for Vec<String> {
         fn from(x: Log) -> Self {
@@ -208,6 +219,9 @@ pub mod handles {
     pub fn action(uuid: Uuid) -> Action {
         Action { uuid }
     }
+    pub fn delivery(uuid: Uuid) -> Delivery {
+        Delivery { uuid }
+    }
 }
 pub mod data {
     pub use crate::task_status::TaskStatusKind;
@@ -217,9 +231,37 @@ pub mod data {
     pub enum User {
         Admin,
     }
+
+    /// The aggregate outcome of the jobs of an evaluation
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        Hash,
+        Serialize,
+        Deserialize,
+        derive_more::Display,
+    )]
+    #[serde(rename_all = "snake_case")]
+    pub enum EvaluationOutcome {
+        /// Some jobs are not finished yet
+        #[default]
+        #[display("pending")]
+        Pending,
+        /// All jobs succeeded, or were skipped
+        #[display("pass")]
+        Pass,
+        /// The evaluation or some jobs failed
+        #[display("fail")]
+        Fail,
+    }
 }
 
 pub mod requests {
+    use crate::data::TaskStatusKind;
     use crate::handles;
 
     use serde::{Deserialize, Serialize};
@@ -246,6 +288,8 @@ pub mod requests {
             Actions(Action),
             #[display("runs")]
             Runs(Run),
+            #[display("deliveries")]
+            Deliveries(Delivery),
         }
 
         #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -263,7 +307,13 @@ pub mod requests {
 
         #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
         pub struct Evaluation {
+            /// Whether the evaluations are of past revisions, made for
+            /// bisections
+            #[serde(default)]
+            pub bisection: Option<bool>,
             pub jobset_name: Option<String>,
+            #[serde(default)]
+            pub outcome: Option<crate::data::EvaluationOutcome>,
             pub project_name: Option<String>,
             pub status: Option<TaskStatusKind>,
         }
@@ -282,6 +332,11 @@ pub mod requests {
             pub status: Option<TaskStatusKind>,
         }
 
+        #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+        pub struct Delivery {
+            pub project_name: Option<String>,
+        }
+
         #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
         pub struct Run {
             pub evaluation_uuid: Option<Uuid>,
@@ -291,10 +346,99 @@ pub mod requests {
         }
     }
 
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum JobsetKind {
+        #[default]
+        Branch,
+        PullRequest,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct JobsetDecl {
         pub flake: bool,
         pub url: String,
+        #[serde(default)]
+        pub kind: JobsetKind,
+        /// Close the jobset after this many seconds without being evaluated
+        #[serde(default)]
+        pub expiry: Option<u64>,
+        #[serde(default)]
+        pub description: String,
+        /// Disabled jobsets cannot be evaluated
+        #[serde(default = "JobsetDecl::default_enabled")]
+        pub enabled: bool,
+        /// Hidden jobsets are not listed with the others
+        #[serde(default)]
+        pub hidden: bool,
+        /// Number of recent evaluations whose builds are protected from
+        /// garbage collection, all of them if unset
+        #[serde(default)]
+        pub keep: Option<u32>,
+        /// Jobsets with a higher priority come first
+        #[serde(default)]
+        pub priority: i32,
+        /// A pattern on job names, where `*` matches any sequence of
+        /// characters, selecting the jobs whose builds are rebuilt to check
+        /// that they are deterministic
+        #[serde(default)]
+        pub checked_jobs: Option<String>,
+        /// How failed job runs are retried, never if unset
+        #[serde(default)]
+        pub retry: Option<RetryPolicy>,
+        /// A pattern on job names, where `*` matches any sequence of
+        /// characters, selecting the jobs required for an evaluation to pass
+        /// its gate
+        #[serde(default)]
+        pub required_jobs: Option<String>,
+    }
+
+    impl JobsetDecl {
+        fn default_enabled() -> bool {
+            true
+        }
+    }
+
+    /// When and how often the runs of a job are automatically retried
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct RetryPolicy {
+        /// Maximum number of runs of a job, including the first one
+        pub max_attempts: u32,
+        /// Delay before the first retry, in seconds, doubled after each retry
+        #[serde(default)]
+        pub backoff: u64,
+        /// The statuses of a failed run that trigger a retry
+        #[serde(default = "RetryPolicy::default_on")]
+        pub on: Vec<TaskStatusKind>,
+    }
+
+    impl RetryPolicy {
+        fn default_on() -> Vec<TaskStatusKind> {
+            vec![
+                TaskStatusKind::TimedOut,
+                TaskStatusKind::InfrastructureError,
+            ]
+        }
+
+        /// Whether a run with the given number and status should be retried
+        pub fn applies(&self, num: u32, status: TaskStatusKind) -> bool {
+            num < self.max_attempts && self.on.contains(&status)
+        }
+
+        /// Delay before retrying a run with the given number, in seconds
+        pub fn delay(&self, num: u32) -> u64 {
+            self.backoff
+                .saturating_mul(1 << num.saturating_sub(1).min(16))
+        }
+    }
+
+    impl Default for RetryPolicy {
+        fn default() -> Self {
+            Self {
+                max_attempts: 1,
+                backoff: 0,
+                on: Self::default_on(),
+            }
+        }
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -303,27 +447,183 @@ pub mod requests {
         pub url: String,
     }
 
+    /// Sandboxing settings of actions. Unset fields fall back to the defaults:
+    /// no limits and network access.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct SandboxSettings {
+        /// Extra paths bound read-only in the sandbox
+        #[serde(default)]
+        pub binds: Vec<String>,
+        /// Maximum CPU time of each process, in seconds
+        #[serde(default)]
+        pub cpu: Option<u64>,
+        /// Environment variables passed through to the sandbox, with their
+        /// values in the environment of the Typhon server
+        #[serde(default)]
+        pub env: Vec<String>,
+        /// Maximum size of the address space of each process, in bytes
+        #[serde(default)]
+        pub memory: Option<u64>,
+        #[serde(default)]
+        pub network: Option<bool>,
+        /// Wall-clock timeout, in seconds
+        #[serde(default)]
+        pub timeout: Option<u64>,
+    }
+
+    /// The sandbox policy of a project: default settings for all actions,
+    /// which can be overridden for each action
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct SandboxPolicy {
+        #[serde(default, flatten)]
+        pub default: SandboxSettings,
+        #[serde(default)]
+        pub actions: std::collections::HashMap<String, SandboxSettings>,
+    }
+
+    impl SandboxPolicy {
+        /// Settings of a given action: its own settings take precedence over
+        /// the default ones, lists are concatenated.
+        pub fn settings(&self, action: &str) -> SandboxSettings {
+            let default = self.default.clone();
+            match self.actions.get(action) {
+                Some(settings) => SandboxSettings {
+                    binds: [default.binds, settings.binds.clone()].concat(),
+                    cpu: settings.cpu.or(default.cpu),
+                    env: [default.env, settings.env.clone()].concat(),
+                    memory: settings.memory.or(default.memory),
+                    network: settings.network.or(default.network),
+                    timeout: settings.timeout.or(default.timeout),
+                },
+                None => default,
+            }
+        }
+    }
+
+    /// The forges Typhon can natively report commit statuses to
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, derive_more::Display)]
+    pub enum ForgeKind {
+        #[display("GitHub")]
+        GitHub,
+        #[display("Gitea")]
+        Gitea,
+        #[display("GitLab")]
+        GitLab,
+    }
+
+    /// Where and how to report the statuses of a project's runs and
+    /// evaluations
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ForgeSettings {
+        pub kind: ForgeKind,
+        /// Base URL of the API, e.g. `https://api.github.com`,
+        /// `https://gitea.example.com/api/v1` or `https://gitlab.com/api/v4`
+        pub api: String,
+        /// The repository, e.g. `owner/repo`
+        pub repository: String,
+        pub token: String,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum Project {
         //Delete,
         Info,
+        /// The latest builds of the project found to be non-deterministic,
+        /// one per derivation
+        NonDeterministicBuilds,
         Refresh,
         SetDecl(ProjectDecl),
         UpdateJobsets,
-        NewJobset { name: String, decl: JobsetDecl },
-        DeleteJobset { name: String },
+        NewJobset {
+            name: String,
+            decl: JobsetDecl,
+        },
+        DeleteJobset {
+            name: String,
+        },
+        RotateKey {
+            grace_period: u64,
+        },
+        EncryptSecrets(serde_json::Value),
+        SetSandbox(SandboxPolicy),
+        RunAction {
+            name: String,
+            input: serde_json::Value,
+        },
+        SetWebhookSecret(Option<String>),
+        SetForge(Option<ForgeSettings>),
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum Jobset {
+        /// Looks for the evaluation where a failing job started failing,
+        /// evaluating past revisions if needed
+        Bisect(String),
+        /// The latest bisection of a job
+        Bisection(String),
         Evaluate(bool),
+        FlakyJobs,
         Info,
+        /// The instances of a job in the evaluations of the jobset, the newest
+        /// first
+        JobHistory {
+            job: String,
+            offset: u32,
+            limit: u8,
+        },
+        /// The newest evaluation of the jobset that passed its gate
+        Latest,
+        /// The newest instance of a job of the jobset that succeeded
+        LatestJob(String),
+        /// The statuses of the jobs of the given number of latest evaluations
+        Matrix(u8),
+        Quarantine(QuarantineDecl),
+        Quarantines,
+        SetDecl(JobsetDecl),
+        Unquarantine(String),
+    }
+
+    /// Quarantines a job of a jobset: its failures no longer count toward the
+    /// status of its evaluations
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct QuarantineDecl {
+        pub job: String,
+        pub reason: String,
+        /// Duration of the quarantine, in seconds
+        pub duration: u64,
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum Evaluation {
         Cancel,
+        /// Compares the jobs of the evaluation with those of another one, by
+        /// default the previous evaluation of its jobset
+        Compare(Option<crate::handles::Evaluation>),
+        /// The gate of the evaluation, without loading its jobs
+        Gate,
         Info,
+        RerunFailed(RerunFilter),
+    }
+
+    /// The stages of a run
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum RunStage {
+        Begin,
+        Build,
+        End,
+    }
+
+    /// Selects the failed jobs of an evaluation to rerun. Unset fields match
+    /// all failed jobs.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct RerunFilter {
+        /// A pattern on job names, where `*` matches any sequence of
+        /// characters
+        #[serde(default)]
+        pub name: Option<String>,
+        /// The stage at which the jobs failed
+        #[serde(default)]
+        pub stage: Option<RunStage>,
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -346,6 +646,14 @@ pub mod requests {
     pub enum Run {
         //Cancel,
         Info,
+        RerunBegin,
+        RerunEnd,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum Delivery {
+        Info,
+        Replay,
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, derive_more::Display)]
@@ -368,6 +676,8 @@ pub mod requests {
         Action(handles::Action, Action),
         #[display("{_1:?} for run {_0}")]
         Run(handles::Run, Run),
+        #[display("{_1:?} for webhook delivery {_0}")]
+        Delivery(handles::Delivery, Delivery),
         #[display("Log in")]
         Login { password: String },
         #[display("Get current user")]
@@ -378,7 +688,7 @@ pub mod requests {
 pub mod responses {
     use crate::data;
     use crate::handles;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     pub use crate::task_status::{TaskStatus, TaskStatusKind, TimeRange};
     use serde::{Deserialize, Serialize};
@@ -394,33 +704,193 @@ pub mod responses {
         pub title: String,
     }
 
+    /// An action declared by a project, beside the predefined ones, that is
+    /// triggered manually
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct CustomAction {
+        #[serde(default)]
+        pub description: String,
+        /// The JSON schema of the action's input
+        #[serde(default = "CustomAction::default_schema")]
+        pub schema: serde_json::Value,
+    }
+
+    impl CustomAction {
+        fn default_schema() -> serde_json::Value {
+            serde_json::Value::Object(Default::default())
+        }
+    }
+
+    /// Why the last refresh of a project failed
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, derive_more::Display)]
+    pub enum RefreshFailure {
+        #[display("Bad project declaration")]
+        BadDecl,
+        #[display("Nix error")]
+        Nix,
+        #[display("Secrets are not encrypted for the project key")]
+        SecretsWrongRecipient,
+        #[display("Secrets are not a valid encrypted JSON file")]
+        InvalidSecrets,
+        #[display("Invalid custom actions")]
+        InvalidCustomActions,
+        #[display("Internal error")]
+        Internal,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct ProjectInfo {
         pub handle: handles::Project,
         pub actions_path: Option<String>,
+        pub custom_actions: std::collections::BTreeMap<String, CustomAction>,
         pub flake: bool,
+        pub forge: Option<ForgeInfo>,
+        pub has_webhook_secret: bool,
         pub jobsets: Vec<String>,
+        pub closed_jobsets: Vec<String>,
+        pub hidden_jobsets: Vec<String>,
         pub last_refresh: Option<TaskStatus>,
+        pub last_refresh_failure: Option<RefreshFailure>,
         pub metadata: ProjectMetadata,
+        #[serde(with = "time::serde::timestamp::option")]
+        pub previous_key_expiry: Option<OffsetDateTime>,
+        pub previous_public_key: Option<String>,
         pub public_key: String,
+        pub sandbox: crate::requests::SandboxPolicy,
         pub url: String,
         pub url_locked: String,
     }
 
+    /// The forge settings of a project, without the token
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ForgeInfo {
+        pub kind: crate::requests::ForgeKind,
+        pub api: String,
+        pub repository: String,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct JobsetInfo {
         pub handle: handles::Jobset,
-        pub flake: bool,
-        pub url: String,
+        pub decl: crate::requests::JobsetDecl,
+        #[serde(with = "time::serde::timestamp::option")]
+        pub time_closed: Option<OffsetDateTime>,
+    }
+
+    /// A job of a jobset that passed only after being retried
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct FlakyJob {
+        pub name: String,
+        /// Number of evaluations in which the job was flaky
+        pub flaky: u32,
+        /// Number of evaluations of the job
+        pub total: u32,
+        /// The latest flaky instance of the job
+        pub last: handles::Job,
+    }
+
+    /// The search for the evaluation of a jobset where a job started failing
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Bisection {
+        pub job: String,
+        /// The oldest evaluation known to fail, the result once finished
+        pub first_bad: handles::Evaluation,
+        /// The newest evaluation before it known to succeed
+        pub last_good: Option<handles::Evaluation>,
+        /// The evaluation of the revision being tested
+        pub probe: Option<handles::Evaluation>,
+        /// Number of revisions left to test
+        pub remaining: u32,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_created: OffsetDateTime,
+        #[serde(with = "time::serde::timestamp::option")]
+        pub time_finished: Option<OffsetDateTime>,
+    }
+
+    /// A page of the history of a job across the evaluations of its jobset
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct JobHistory {
+        /// Number of evaluations with the job
+        pub total: u32,
+        pub entries: Vec<JobHistoryEntry>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct JobHistoryEntry {
+        pub handle: handles::Job,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_created: OffsetDateTime,
+        /// The status of the last run of the job
+        pub status: TaskStatus,
+        /// Duration of the build of the last run, in seconds, once finished
+        pub build_duration: Option<i64>,
+        pub drv: String,
+        /// Whether the derivation differs from the one of the previous
+        /// evaluation with the job
+        pub drv_changed: bool,
+    }
+
+    /// The statuses of the jobs of the latest evaluations of a jobset
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct JobsetMatrix {
+        /// The evaluations, the newest first
+        pub evaluations: Vec<MatrixEvaluation>,
+        /// The jobs of any of the evaluations, sorted by name
+        pub jobs: Vec<MatrixJob>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct MatrixEvaluation {
+        pub handle: handles::Evaluation,
+        pub outcome: crate::data::EvaluationOutcome,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_created: OffsetDateTime,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct MatrixJob {
+        pub name: String,
+        /// The last run of the job in each evaluation, in the order of the
+        /// evaluations, and its status
+        pub cells: Vec<Option<(handles::Run, TaskStatus)>>,
+    }
+
+    /// The locked attributes of a flake, as recorded in its `flake.lock`
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct FlakeLock {
+        pub rev: Option<String>,
+        pub last_modified: Option<i64>,
+        pub nar_hash: Option<String>,
+        /// The locks of the inputs of the flake, by name. Inputs that follow
+        /// another input are listed without their own inputs.
+        #[serde(default)]
+        pub inputs: BTreeMap<String, FlakeLock>,
+    }
+
+    /// The commit of an evaluation's revision, as reported by the forge
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Commit {
+        pub author: String,
+        pub subject: String,
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct EvaluationInfo {
         pub handle: handles::Evaluation,
         pub actions_path: Option<String>,
+        pub commit: Option<Commit>,
         pub flake: bool,
+        /// The aggregate outcome of the required jobs, or of all jobs if none
+        /// is required
+        pub gate: crate::data::EvaluationOutcome,
         pub jobs: HashMap<String, JobInfo>,
+        /// The number of jobs with each status, as they count toward the
+        /// outcome
+        pub job_counts: HashMap<crate::data::TaskStatusKind, u32>,
         pub jobset_name: String,
+        /// The lock of the flake and of its inputs, if it was recorded
+        pub lock: Option<FlakeLock>,
+        pub outcome: crate::data::EvaluationOutcome,
         pub project: handles::Project,
         pub status: TaskStatus,
         #[serde(with = "time::serde::timestamp")]
@@ -428,6 +898,23 @@ pub mod responses {
         pub url: String,
     }
 
+    /// How the jobs of an evaluation differ from those of another one
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct EvaluationComparison {
+        /// The evaluation compared against
+        pub other: handles::Evaluation,
+        /// Jobs that are not in the other evaluation
+        pub added: Vec<String>,
+        /// Jobs of the other evaluation that are gone
+        pub removed: Vec<String>,
+        /// Jobs whose derivation changed
+        pub changed: Vec<String>,
+        /// Jobs that failed but did not fail in the other evaluation
+        pub newly_failing: Vec<String>,
+        /// Jobs that succeeded but failed in the other evaluation
+        pub fixed: Vec<String>,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct JobInfo {
         pub handle: handles::Job,
@@ -436,31 +923,160 @@ pub mod responses {
         pub out: String,
         pub last_run: RunInfo,
         pub run_count: u32,
+        /// Whether the job passed only after being retried
+        pub flaky: bool,
+        /// Whether the job is required for its evaluation to pass its gate
+        pub required: bool,
+        /// The quarantine of the job in its jobset, if it is quarantined
+        pub quarantine: Option<Quarantine>,
+    }
+
+    impl JobInfo {
+        /// The status of the job as it counts toward the status of its
+        /// evaluation: the failures of a quarantined job are skipped
+        pub fn counted_status(&self) -> TaskStatus {
+            let status = TaskStatus::from(self);
+            let kind = crate::data::TaskStatusKind::from(&status);
+            if self.quarantine.is_some() && kind.is_failure() {
+                let (start, end) = status.times();
+                crate::data::TaskStatusKind::Skipped.into_task_status(start, end)
+            } else {
+                status
+            }
+        }
+    }
+
+    /// A quarantine of a job in a jobset. Quarantines are kept once expired or
+    /// lifted, as an audit trail.
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Quarantine {
+        pub job: String,
+        pub reason: String,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_created: OffsetDateTime,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_expires: OffsetDateTime,
+        #[serde(with = "time::serde::timestamp::option")]
+        pub time_lifted: Option<OffsetDateTime>,
+    }
+
+    impl Quarantine {
+        pub fn is_active(&self, now: OffsetDateTime) -> bool {
+            self.time_lifted.is_none() && now < self.time_expires
+        }
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct BuildInfo {
         pub handle: handles::Build,
         pub drv: String,
+        /// The dependency whose failure prevented the build
+        pub failed_dependency: Option<handles::Build>,
+        pub outcome: Option<BuildOutcome>,
         pub status: TaskStatus,
     }
 
+    /// How a finished build went, unknown for builds that failed or are still
+    /// running
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum BuildOutcome {
+        /// The outputs were built or substituted
+        Built,
+        /// The outputs were rebuilt and did not change
+        Deterministic,
+        /// The outputs were rebuilt and differ from the previous ones
+        NonDeterministic,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ActionLink {
+        pub name: String,
+        pub url: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ActionAnnotation {
+        pub key: String,
+        pub value: String,
+    }
+
+    /// Structured results reported by an action on its standard error
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ActionResults {
+        #[serde(default)]
+        pub annotations: Vec<ActionAnnotation>,
+        #[serde(default)]
+        pub links: Vec<ActionLink>,
+        #[serde(default)]
+        pub summary: Option<String>,
+    }
+
+    /// Why an action failed, when it was stopped by Typhon rather than
+    /// failing by itself
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, derive_more::Display)]
+    pub enum ActionFailure {
+        #[display("Exceeded the CPU time limit")]
+        CpuLimitExceeded,
+        #[display("Exceeded the output size limit")]
+        OutputTooLarge,
+        #[display("Invalid secrets")]
+        InvalidSecrets,
+        #[display("Timed out")]
+        TimedOut,
+        #[display("Internal error")]
+        Internal,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct ActionInfo {
         pub handle: handles::Action,
+        pub exit_code: Option<i32>,
+        pub failure: Option<ActionFailure>,
         pub input: String,
         pub name: String,
         pub path: String,
         pub project: handles::Project,
+        pub results: ActionResults,
         pub status: TaskStatus,
     }
 
+    /// How the processing of a webhook delivery ended
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, derive_more::Display)]
+    pub enum DeliveryOutcome {
+        #[display("Success")]
+        Success,
+        #[display("The webhook action failed")]
+        ActionFailure,
+        #[display("The webhook action output invalid commands")]
+        InvalidCommands,
+        #[display("A command failed: {_0}")]
+        CommandFailure(String),
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct DeliveryInfo {
+        pub handle: handles::Delivery,
+        pub action: handles::Action,
+        pub body: String,
+        /// The commands output by the webhook action, once it has finished
+        pub commands: Option<serde_json::Value>,
+        pub headers: HashMap<String, String>,
+        /// The outcome of the delivery, or `None` while it is being processed
+        pub outcome: Option<DeliveryOutcome>,
+        pub project: handles::Project,
+        #[serde(with = "time::serde::timestamp")]
+        pub time_created: OffsetDateTime,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct RunInfo {
         pub handle: handles::Run,
         pub begin: Option<ActionInfo>,
         pub build: Option<BuildInfo>,
         pub end: Option<ActionInfo>,
+        /// Actions spawned again on the run, oldest first. Only listed in run
+        /// infos, not in the last run of job infos.
+        pub reruns: Vec<ActionInfo>,
     }
 
     pub mod search {
@@ -473,6 +1089,7 @@ pub mod responses {
             Builds(Vec<handles::Build>),
             Actions(Vec<handles::Action>),
             Runs(Vec<handles::Run>),
+            Deliveries(Vec<handles::Delivery>),
             Projects(Vec<(handles::Project, crate::responses::ProjectMetadata)>),
         }
         #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -487,13 +1104,28 @@ pub mod responses {
         Ok,
         Search(search::Info),
         ProjectInfo(ProjectInfo),
+        ProjectNonDeterministicBuilds(Vec<BuildInfo>),
+        ProjectEncryptSecrets(String),
+        ProjectRunAction(crate::handles::Action),
+        JobsetBisection(Option<Bisection>),
         JobsetEvaluate(crate::handles::Evaluation),
+        JobsetFlakyJobs(Vec<FlakyJob>),
         JobsetInfo(JobsetInfo),
+        JobsetJobHistory(JobHistory),
+        JobsetLatest(crate::handles::Evaluation),
+        JobsetLatestJob(crate::handles::Job),
+        JobsetMatrix(JobsetMatrix),
+        JobsetQuarantines(Vec<Quarantine>),
+        EvaluationCompare(EvaluationComparison),
+        EvaluationGate(crate::data::EvaluationOutcome),
         EvaluationInfo(EvaluationInfo),
+        EvaluationRerunFailed(u32),
         JobInfo(JobInfo),
         BuildInfo(BuildInfo),
         ActionInfo(ActionInfo),
         RunInfo(RunInfo),
+        DeliveryInfo(DeliveryInfo),
+        DeliveryReplay(crate::handles::Delivery),
         User(Option<data::User>),
     }
 
@@ -516,12 +1148,21 @@ pub enum Event {
     ProjectUpdated(handles::Project),
     EvaluationNew(handles::Evaluation),
     EvaluationFinished(handles::Evaluation),
+    /// The outcome of the jobs of an evaluation is known
+    EvaluationSettled(handles::Evaluation),
+    /// A bisection of a job of a jobset progressed
+    BisectionUpdated(handles::Jobset),
     BuildNew(handles::Build),
     BuildFinished(handles::Build),
     RunNew(handles::Run),
     RunUpdated(handles::Run),
+    /// Runs of the jobs of a jobset were created or updated, or one of its
+    /// evaluations settled
+    JobsUpdated(handles::Jobset),
     ActionNew(handles::Action),
     ActionFinished(handles::Action),
+    DeliveryNew(handles::Delivery),
+    DeliveryFinished(handles::Delivery),
 }
 
 impl Event {
@@ -533,19 +1174,51 @@ impl Event {
                 use search::Kind as Search;
                 match (kind, self) {
                     (Search::Projects, Ev::ProjectNew(_) | Ev::ProjectUpdated(_))
-                    | (Search::Evaluations(_), Ev::EvaluationNew(_) | Ev::EvaluationFinished(_))
+                    | (
+                        Search::Evaluations(_),
+                        Ev::EvaluationNew(_) | Ev::EvaluationFinished(_) | Ev::EvaluationSettled(_),
+                    )
                     | (Search::Runs(_), Ev::RunUpdated(_) | Ev::RunNew(_))
                     | (Search::Builds(_), Ev::BuildNew(_) | Ev::BuildFinished(_))
-                    | (Search::Actions(_), Ev::ActionNew(_) | Ev::ActionFinished(_)) => true,
+                    | (Search::Actions(_), Ev::ActionNew(_) | Ev::ActionFinished(_))
+                    | (Search::Deliveries(_), Ev::DeliveryNew(_) | Ev::DeliveryFinished(_)) => true,
                     _ => false,
                 }
             }
-            (Ev::ProjectUpdated(h1), Req::Project(h2, Project::Info)) => h1 == h2,
-            (Ev::ProjectUpdated(h1), Req::Jobset(h2, Jobset::Info)) => *h1 == h2.project,
-            (Ev::EvaluationFinished(h1), Req::Evaluation(h2, Evaluation::Info)) => h1 == h2,
+            (
+                Ev::ProjectUpdated(h1),
+                Req::Project(h2, Project::Info | Project::NonDeterministicBuilds),
+            ) => h1 == h2,
+            (Ev::BisectionUpdated(h1), Req::Jobset(h2, Jobset::Bisection(_))) => h1 == h2,
+            (Ev::ProjectUpdated(h1), Req::Jobset(h2, Jobset::Info | Jobset::Quarantines)) => {
+                *h1 == h2.project
+            }
+            (
+                Ev::EvaluationFinished(h1) | Ev::EvaluationSettled(h1),
+                Req::Evaluation(h2, Evaluation::Gate | Evaluation::Info),
+            ) => h1 == h2,
+            (
+                Ev::EvaluationFinished(_) | Ev::RunUpdated(_),
+                Req::Evaluation(_, Evaluation::Compare(_)),
+            ) => true,
+            (
+                Ev::EvaluationNew(_) | Ev::EvaluationFinished(_),
+                Req::Jobset(_, Jobset::JobHistory { .. } | Jobset::Matrix(_)),
+            ) => true,
+            (
+                Ev::JobsUpdated(h1),
+                Req::Jobset(
+                    h2,
+                    Jobset::JobHistory { .. }
+                    | Jobset::Latest
+                    | Jobset::LatestJob(_)
+                    | Jobset::Matrix(_),
+                ),
+            ) => h1 == h2,
             (Ev::BuildFinished(h1), Req::Build(h2, Build::Info)) => h1 == h2,
             (Ev::RunUpdated(h1), Req::Run(h2, Run::Info)) => h1 == h2,
             (Ev::ActionFinished(h1), Req::Action(h2, Action::Info)) => h1 == h2,
+            (Ev::DeliveryFinished(h1), Req::Delivery(h2, Delivery::Info)) => h1 == h2,
             (_, _) => false,
         }
     }
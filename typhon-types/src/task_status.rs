@@ -15,6 +15,15 @@ pub enum TaskStatus {
     Success(TimeRange),
     /** The task is done and failed */
     Failure(TimeRange),
+    /** The task was stopped for exceeding its time limits */
+    TimedOut(TimeRange),
+    /** The task did not run because one of its dependencies failed */
+    DependencyFailed(TimeRange),
+    /** The task failed because of Typhon or of the system, not because of
+     * what it was running */
+    InfrastructureError(TimeRange),
+    /** The task had nothing to do, e.g. an action that is not defined */
+    Skipped(Option<TimeRange>),
     /** The task was canceled: either while running (then the payload
      * is a `Some(TimeRange {start,end})`) or before running. */
     // TODO: we should have either a TimeRange or a {end}, right?
@@ -42,7 +51,7 @@ impl Default for TaskStatus {
     strum::EnumIter,
     derive_more::Display,
 )]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum TaskStatusKind {
     #[default]
@@ -54,6 +63,14 @@ pub enum TaskStatusKind {
     Failure = 2,
     #[display("canceled")]
     Canceled = 3,
+    #[display("timed_out")]
+    TimedOut = 4,
+    #[display("dependency_failed")]
+    DependencyFailed = 5,
+    #[display("infrastructure_error")]
+    InfrastructureError = 6,
+    #[display("skipped")]
+    Skipped = 7,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -77,6 +94,10 @@ impl From<&TaskStatus> for TaskStatusKind {
             TaskStatus::Success(..) => Self::Success,
             TaskStatus::Failure(..) => Self::Failure,
             TaskStatus::Canceled(..) => Self::Canceled,
+            TaskStatus::TimedOut(..) => Self::TimedOut,
+            TaskStatus::DependencyFailed(..) => Self::DependencyFailed,
+            TaskStatus::InfrastructureError(..) => Self::InfrastructureError,
+            TaskStatus::Skipped(..) => Self::Skipped,
         }
     }
 }
@@ -91,6 +112,12 @@ const SUCCESS_TIME_INVARIANT: &str =
     "a `TaskStatus::Success` requires a start time and an end time";
 const FAILURE_TIME_INVARIANT: &str =
     "a `TaskStatus::Failure` requires a start time and an end time";
+const TIMED_OUT_TIME_INVARIANT: &str =
+    "a `TaskStatus::TimedOut` requires a start time and an end time";
+const DEPENDENCY_FAILED_TIME_INVARIANT: &str =
+    "a `TaskStatus::DependencyFailed` requires a start time and an end time";
+const INFRASTRUCTURE_ERROR_TIME_INVARIANT: &str =
+    "a `TaskStatus::InfrastructureError` requires a start time and an end time";
 impl TaskStatusKind {
     /** Promotes a `TaskStatusKind` to a `TaskStatus`, given a start
      * time and a finish time. Note those are optional: a success task
@@ -107,8 +134,23 @@ impl TaskStatusKind {
             Self::Success => TaskStatus::Success(range.expect(SUCCESS_TIME_INVARIANT)),
             Self::Failure => TaskStatus::Failure(range.expect(FAILURE_TIME_INVARIANT)),
             Self::Canceled => TaskStatus::Canceled(range),
+            Self::TimedOut => TaskStatus::TimedOut(range.expect(TIMED_OUT_TIME_INVARIANT)),
+            Self::DependencyFailed => {
+                TaskStatus::DependencyFailed(range.expect(DEPENDENCY_FAILED_TIME_INVARIANT))
+            }
+            Self::InfrastructureError => {
+                TaskStatus::InfrastructureError(range.expect(INFRASTRUCTURE_ERROR_TIME_INVARIANT))
+            }
+            Self::Skipped => TaskStatus::Skipped(range),
         }
     }
+    /** Whether a task with this status is done and did not succeed */
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            Self::Failure | Self::TimedOut | Self::DependencyFailed | Self::InfrastructureError
+        )
+    }
 }
 impl TaskStatus {
     /** Extracts the (possibly non-exsitent) start and finish times of
@@ -116,10 +158,14 @@ impl TaskStatus {
     pub fn times(self) -> (Option<OffsetDateTime>, Option<OffsetDateTime>) {
         match self {
             Self::Pending { start } => (start, None),
-            Self::Success(range) | Self::Failure(range) | Self::Canceled(Some(range)) => {
-                (Some(range.start), Some(range.end))
-            }
-            Self::Canceled(None) => (None, None),
+            Self::Success(range)
+            | Self::Failure(range)
+            | Self::TimedOut(range)
+            | Self::DependencyFailed(range)
+            | Self::InfrastructureError(range)
+            | Self::Skipped(Some(range))
+            | Self::Canceled(Some(range)) => (Some(range.start), Some(range.end)),
+            Self::Canceled(None) | Self::Skipped(None) => (None, None),
         }
     }
     pub fn union(&self, rhs: &Self) -> Self {
@@ -129,20 +175,23 @@ impl TaskStatus {
         let end = lhs_end.max(rhs_end);
         let lhs_kind: TaskStatusKind = self.into();
         let rhs_kind: TaskStatusKind = rhs.into();
-        let range = start.zip(end).map(|(start, end)| TimeRange { start, end });
-        match lhs_kind.max(rhs_kind) {
-            TaskStatusKind::Failure => Self::Failure(range.expect(FAILURE_TIME_INVARIANT)),
-            TaskStatusKind::Pending => Self::Pending { start },
-            TaskStatusKind::Canceled => Self::Canceled(range),
-            TaskStatusKind::Success => Self::Success(range.expect(SUCCESS_TIME_INVARIANT)),
-        }
+        lhs_kind.max(rhs_kind).into_task_status(start, end)
     }
 }
 
 impl TryFrom<i32> for TaskStatusKind {
     type Error = ();
     fn try_from(n: i32) -> Result<TaskStatusKind, ()> {
-        let arr = [Self::Pending, Self::Success, Self::Failure, Self::Canceled];
+        let arr = [
+            Self::Pending,
+            Self::Success,
+            Self::Failure,
+            Self::Canceled,
+            Self::TimedOut,
+            Self::DependencyFailed,
+            Self::InfrastructureError,
+            Self::Skipped,
+        ];
         arr.get(n as usize).ok_or(()).copied()
     }
 }
@@ -159,19 +208,20 @@ impl core::cmp::PartialOrd for TaskStatusKind {
 }
 impl core::cmp::Ord for TaskStatusKind {
     fn cmp(&self, rhs: &Self) -> core::cmp::Ordering {
-        use core::cmp::Ordering;
-        if self == rhs {
-            return Ordering::Equal;
-        }
-        match (self, rhs) {
-            (TaskStatusKind::Failure, _) => Ordering::Greater,
-            (_, TaskStatusKind::Failure) => Ordering::Less,
-            (TaskStatusKind::Pending, _) => Ordering::Greater,
-            (_, TaskStatusKind::Pending) => Ordering::Less,
-            (TaskStatusKind::Canceled, _) => Ordering::Greater,
-            (_, TaskStatusKind::Canceled) => Ordering::Less,
-            (TaskStatusKind::Success, TaskStatusKind::Success) => Ordering::Greater,
+        /* failures come first, then pending tasks, then the others */
+        fn rank(kind: &TaskStatusKind) -> u8 {
+            match kind {
+                TaskStatusKind::Success => 0,
+                TaskStatusKind::Skipped => 1,
+                TaskStatusKind::Canceled => 2,
+                TaskStatusKind::Pending => 3,
+                TaskStatusKind::InfrastructureError => 4,
+                TaskStatusKind::DependencyFailed => 5,
+                TaskStatusKind::TimedOut => 6,
+                TaskStatusKind::Failure => 7,
+            }
         }
+        rank(self).cmp(&rank(rhs))
     }
 }
 
@@ -193,10 +243,19 @@ impl From<&crate::responses::RunInfo> for TaskStatus {
                 TaskStatusKind::Pending
             }
             (
+                Some(TaskStatusKind::Success | TaskStatusKind::Skipped),
                 Some(TaskStatusKind::Success),
-                Some(TaskStatusKind::Success),
-                Some(TaskStatusKind::Success),
+                Some(TaskStatusKind::Success | TaskStatusKind::Skipped),
             ) => TaskStatusKind::Success,
+            (
+                _,
+                Some(
+                    kind @ (TaskStatusKind::TimedOut
+                    | TaskStatusKind::DependencyFailed
+                    | TaskStatusKind::InfrastructureError),
+                ),
+                _,
+            ) => kind,
             _ => TaskStatusKind::Failure,
         };
         kind.into_task_status(start, end)
@@ -109,6 +109,18 @@ pub fn App() -> impl IntoView {
         :deep(*[data-status=Error]) {
             --color-task-status: var(--color-task-status-error);
         }
+        :deep(*[data-status=TimedOut]) {
+            --color-task-status: var(--color-task-status-error);
+        }
+        :deep(*[data-status=DependencyFailed]) {
+            --color-task-status: var(--color-task-status-error);
+        }
+        :deep(*[data-status=InfrastructureError]) {
+            --color-task-status: var(--color-task-status-pending);
+        }
+        :deep(*[data-status=Skipped]) {
+            --color-task-status: var(--color-task-status-canceled);
+        }
         :deep(*[data-status=Canceled]) {
             --color-task-status: var(--color-task-status-canceled);
         }
@@ -92,10 +92,14 @@ impl EvalStatus {
     pub fn hybrid_status(&self) -> HybridStatusKind {
         match TaskStatusKind::from(&self.eval) {
             TaskStatusKind::Pending => HybridStatusKind::EvalPending,
-            TaskStatusKind::Success => HybridStatusKind::EvalSucceeded {
+            TaskStatusKind::Success | TaskStatusKind::Skipped => HybridStatusKind::EvalSucceeded {
                 build: self.jobs.unwrap_or_default().into(),
             },
-            TaskStatusKind::Failure | TaskStatusKind::Canceled => HybridStatusKind::EvalStopped,
+            TaskStatusKind::Failure
+            | TaskStatusKind::Canceled
+            | TaskStatusKind::TimedOut
+            | TaskStatusKind::DependencyFailed
+            | TaskStatusKind::InfrastructureError => HybridStatusKind::EvalStopped,
         }
     }
     pub fn summary(&self) -> TaskStatus {
@@ -110,7 +114,7 @@ impl EvalStatus {
             .jobs
             .clone()
             .into_values()
-            .map(TaskStatus::from)
+            .map(|job| job.counted_status())
             .collect();
         let mut map: HashMap<TaskStatusKind, u32> = HashMap::new();
         for kind in &job_statuses {
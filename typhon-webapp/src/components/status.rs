@@ -63,6 +63,10 @@ pub fn HybridStatus(#[prop(into)] status: Signal<HybridStatusKind>) -> impl Into
                                 TaskStatusKind::Pending => BiLoaderAltRegular,
                                 TaskStatusKind::Failure => BiXCircleSolid,
                                 TaskStatusKind::Canceled => BiStopCircleRegular,
+                                TaskStatusKind::TimedOut => BiTimeRegular,
+                                TaskStatusKind::DependencyFailed => BiUnlinkRegular,
+                                TaskStatusKind::InfrastructureError => BiServerRegular,
+                                TaskStatusKind::Skipped => BiSkipNextCircleRegular,
                             }
                         }
                     };
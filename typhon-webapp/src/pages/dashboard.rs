@@ -21,7 +21,9 @@ pub fn PendingEvaluations(page: u32) -> impl IntoView {
         Signal::derive({
             move || {
                 requests::search::Kind::Evaluations(requests::search::Evaluation {
+                    bisection: None,
                     jobset_name: None,
+                    outcome: None,
                     project_name: None,
                     status: Some(TaskStatusKind::Pending),
                 })
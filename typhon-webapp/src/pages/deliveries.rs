@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+#[component]
+fn Delivery(handle: handles::Delivery) -> impl IntoView {
+    let (error, info) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Delivery(
+                handle.clone(),
+                requests::Delivery::Info
+            )),
+            |responses::Response::DeliveryInfo(info)| info
+        )
+    };
+    let replay = request_action!(ReplayDelivery, |uuid: String| requests::Request::Delivery(
+        handles::delivery(Uuid::parse_str(&uuid).unwrap_or_default()),
+        requests::Delivery::Replay,
+    ));
+    let uuid = handle.uuid.to_string();
+    move || {
+        view! {
+            <Trans error>
+                {info()
+                    .map(|info| {
+                        let mut headers: Vec<_> = info.headers.into_iter().collect();
+                        headers.sort();
+                        view! {
+                            <div class="row">
+                                <div class="column">
+                                    <RelativeTime datetime=info.time_created />
+                                </div>
+                                <div class="column">
+                                    {info
+                                        .outcome
+                                        .map(|outcome| outcome.to_string())
+                                        .unwrap_or("Processing".to_string())}
+                                </div>
+                                <div class="column">
+                                    <UuidLabel uuid=info.action.uuid />
+                                </div>
+                                <div class="column">
+                                    <ActionForm action=replay>
+                                        <input type="hidden" name="uuid" value=uuid.clone() />
+                                        <input type="submit" value="Replay" />
+                                    </ActionForm>
+                                </div>
+                            </div>
+                            <details>
+                                <summary>"Payload"</summary>
+                                <table>
+                                    {headers
+                                        .into_iter()
+                                        .map(|(name, value)| {
+                                            view! {
+                                                <tr>
+                                                    <td>{name}</td>
+                                                    <td>{value}</td>
+                                                </tr>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()}
+                                </table>
+                                <pre>{info.body}</pre>
+                                {info
+                                    .commands
+                                    .map(|commands| {
+                                        view! {
+                                            <pre>
+                                                {serde_json::to_string_pretty(&commands)
+                                                    .unwrap_or_default()}
+                                            </pre>
+                                        }
+                                    })}
+                            </details>
+                        }
+                    })}
+            </Trans>
+        }
+    }
+}
+
+#[component]
+pub fn Deliveries(
+    #[prop(into)] project: handles::Project,
+    #[prop(into)] page: Signal<u32>,
+) -> impl IntoView {
+    let style = style! {
+        .rows :deep(.row), .header-columns {
+            display: grid;
+            grid-template-columns: 1fr 2fr 2fr 1fr;
+            gap: 10px;
+        }
+        .header-columns {
+            padding-top: 6px;
+            color: var(--color-fg-subtle);
+            font-size: var(--font-size-small);
+        }
+        .rows :deep(details) {
+            padding-bottom: 10px;
+        }
+    };
+    let limit = Signal::derive(move || 10 as u8);
+    let offset = Signal::derive(move || (page() - 1) * (limit() as u32));
+    let (error, deliveries) = {
+        let project = project.clone();
+        search!(
+            offset,
+            limit,
+            Signal::derive(move || {
+                requests::search::Kind::Deliveries(requests::search::Delivery {
+                    project_name: Some(project.name.clone()),
+                })
+            }),
+            |total, responses::search::Results::Deliveries(deliveries)| (total, deliveries)
+        )
+    };
+    let deliveries = Signal::derive(move || deliveries().unwrap_or((0, Vec::new())));
+    let count = Signal::derive(move || deliveries().0);
+    let deliveries = Signal::derive(move || deliveries().1);
+    let item_name = project.name.clone();
+    view! { class=style,
+        <PageHeader item_kind="Webhook deliveries" item_name=item_name>
+            <span></span>
+        </PageHeader>
+        <Trans error>
+            <div class="is-table">
+                <div class="header">
+                    <div class="summary">{count}" deliveries"</div>
+                    <div class="header-columns">
+                        <div class="column">"Received"</div>
+                        <div class="column">"Outcome"</div>
+                        <div class="column">"Action"</div>
+                        <div class="column"></div>
+                    </div>
+                </div>
+                <div class="rows">
+                    <For
+                        each=deliveries
+                        key=|handle| handle.clone()
+                        children=move |handle| {
+                            view! { <Delivery handle /> }
+                        }
+                    />
+
+                </div>
+            </div>
+            <Pagination
+                max=10
+                count
+                current=page
+                link={
+                    let project = project.clone();
+                    move |page: u32| String::from(Root::Deliveries {
+                        project: project.clone(),
+                        page,
+                    })
+                }
+            />
+
+        </Trans>
+    }
+}
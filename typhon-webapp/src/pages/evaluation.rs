@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use routes::{EvaluationTab, LogTab};
+use routes::{EvaluationPage, EvaluationTab, LogTab};
 
 use typhon_types::data::TaskStatusKind;
 use typhon_types::responses::TaskStatus;
@@ -98,9 +98,157 @@ fn LogTabHeader(
     }
 }
 
+#[component]
+fn ActionResults(
+    failure: Option<responses::ActionFailure>,
+    results: responses::ActionResults,
+) -> impl IntoView {
+    let style = style! {
+        .results {
+            padding-bottom: 10px;
+        }
+        .results :deep(a) {
+            color: inherit;
+        }
+        .results :deep(.key) {
+            color: #8C959F;
+            padding-right: 5px;
+        }
+        .results :deep(.failure) {
+            color: #CF222E;
+        }
+    };
+    let empty = failure.is_none()
+        && results.summary.is_none()
+        && results.links.is_empty()
+        && results.annotations.is_empty();
+    (!empty).then(|| {
+        view! { class=style,
+            <div class="results">
+                {failure.map(|failure| view! { <p class="failure">{failure.to_string()}</p> })}
+                {results.summary.map(|summary| view! { <p>{summary}</p> })}
+                <ul>
+                    {results
+                        .links
+                        .into_iter()
+                        .map(|link| {
+                            view! {
+                                <li>
+                                    <a href=link.url>{link.name}</a>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </ul>
+                <table>
+                    {results
+                        .annotations
+                        .into_iter()
+                        .map(|annotation| {
+                            view! {
+                                <tr>
+                                    <td class="key">{annotation.key}</td>
+                                    <td>{annotation.value}</td>
+                                </tr>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </table>
+            </div>
+        }
+    })
+}
+
+#[component]
+fn Bisection(jobset: handles::Jobset, job: String, failing: bool) -> impl IntoView {
+    let user: Signal<Option<data::User>> = use_context().unwrap();
+    let (error, bisection) = {
+        let jobset = jobset.clone();
+        let job = job.clone();
+        resource!(
+            Signal::derive(move || Request::Jobset(
+                jobset.clone(),
+                requests::Jobset::Bisection(job.clone())
+            )),
+            |Response::JobsetBisection(bisection)| bisection
+        )
+    };
+    let bisect = request_action!(BisectJob, |project: String, jobset: String, job: String| {
+        requests::Request::Jobset(
+            handles::Jobset {
+                project: handles::Project { name: project },
+                name: jobset,
+            },
+            requests::Jobset::Bisect(job),
+        )
+    });
+    let job_href = {
+        let job = job.clone();
+        move |evaluation: handles::Evaluation| {
+            Root::Evaluation(EvaluationPage {
+                handle: evaluation.clone(),
+                tab: EvaluationTab::Job {
+                    handle: handles::Job {
+                        evaluation,
+                        name: job.clone(),
+                    },
+                    log_tab: LogTab::default(),
+                },
+            })
+        }
+    };
+    let running = move || {
+        bisection()
+            .flatten()
+            .is_some_and(|bisection| bisection.time_finished.is_none())
+    };
+    view! {
+        <Trans error>
+            {move || {
+                bisection()
+                    .flatten()
+                    .map(|bisection| {
+                        let first_bad = job_href(bisection.first_bad.clone());
+                        match bisection.time_finished {
+                            Some(_) => {
+                                view! {
+                                    <p class="bisection">
+                                        "First bad evaluation: " <A href=first_bad>
+                                            <UuidLabel uuid=bisection.first_bad.uuid />
+                                        </A>
+                                    </p>
+                                }
+                            }
+                            None => {
+                                view! {
+                                    <p class="bisection">
+                                        "Bisecting, " {bisection.remaining}
+                                        " revisions left; failing since at least " <A href=first_bad>
+                                            <UuidLabel uuid=bisection.first_bad.uuid />
+                                        </A>
+                                    </p>
+                                }
+                            }
+                        }
+                    })
+            }}
+
+        </Trans>
+        <Show when=move || failing && user().is_some() && !running()>
+            <ActionForm action=bisect>
+                <input type="hidden" name="project" value=jobset.project.name.clone() />
+                <input type="hidden" name="jobset" value=jobset.name.clone() />
+                <input type="hidden" name="job" value=job.clone() />
+                <input type="submit" value="Find the first bad evaluation" />
+            </ActionForm>
+        </Show>
+    }
+}
+
 #[component]
 pub fn JobSubpage(
     #[prop(into)] job: responses::JobInfo,
+    #[prop(into)] jobset: handles::Jobset,
     #[prop(into)] log_tab: LogTab,
 ) -> impl IntoView {
     let style = style! {
@@ -185,7 +333,34 @@ pub fn JobSubpage(
         .find(|(.., tab)| tab == &log_tab)
         .map(|(handle, ..)| handle);
 
+    let active_results = {
+        let run = job.last_run.clone();
+        match log_tab {
+            LogTab::Begin => run.begin.map(|x| (x.failure, x.results)),
+            LogTab::End => run.end.map(|x| (x.failure, x.results)),
+            LogTab::Build => None,
+        }
+    };
+
+    let failed_dependency = match log_tab {
+        LogTab::Build => job
+            .last_run
+            .build
+            .clone()
+            .and_then(|build| build.failed_dependency),
+        _ => None,
+    };
+
     let run = job.last_run.clone();
+    let quarantine = job.quarantine.clone();
+    let failing = TaskStatusKind::from(&TaskStatus::from(run.clone())).is_failure();
+    let bisection =
+        view! { <Bisection jobset=jobset.clone() job=job.handle.name.clone() failing /> };
+    let history = Root::JobHistory {
+        jobset,
+        job: job.handle.name.clone(),
+        page: 1,
+    };
     view! { class=style,
         <div class="header">
             <div class="name">
@@ -221,6 +396,13 @@ pub fn JobSubpage(
                             }
                             TaskStatus::Success(..) => make("succeeded"),
                             TaskStatus::Failure(..) => make("failed"),
+                            TaskStatus::TimedOut(..) => make("timed out"),
+                            TaskStatus::DependencyFailed(..) => make("dependency failed"),
+                            TaskStatus::InfrastructureError(..) => {
+                                make("infrastructure error")
+                            }
+                            TaskStatus::Skipped(Some(..)) => make("skipped"),
+                            TaskStatus::Skipped(None) => view! { <>skipped</> },
                             TaskStatus::Canceled(Some(..)) => make("canceled"),
                             TaskStatus::Canceled(None) => view! { <>canceled</> },
                         }
@@ -228,10 +410,23 @@ pub fn JobSubpage(
 
                 </h2>
             </div>
+            <A href=history>
+                <Icon icon=icondata::BiHistoryRegular />
+            </A>
             <Icon icon=icondata::BiRefreshRegular />
             <Icon icon=icondata::BiCogRegular />
         </div>
         <div class="contents">
+            {quarantine
+                .map(|quarantine| {
+                    view! {
+                        <p class="quarantine">
+                            "Quarantined until " <RelativeTime datetime=quarantine.time_expires />
+                            ": " {quarantine.reason}
+                        </p>
+                    }
+                })}
+            {bisection}
             <div class="tabs">
                 {logs
                     .clone()
@@ -250,12 +445,109 @@ pub fn JobSubpage(
                     .collect::<Vec<_>>()}
             </div>
             <div class="active">
+                {active_results.map(|(failure, results)| view! { <ActionResults failure results /> })}
+                {failed_dependency
+                    .map(|build| {
+                        view! {
+                            <p class="failed-dependency">
+                                "The build of dependency " {build.uuid.to_string()} " failed"
+                            </p>
+                        }
+                    })}
                 {active_log.map(|handle| view! { <LiveLog lines=fetch_log(handle.clone()) /> })}
             </div>
         </div>
     }
 }
 
+#[component]
+fn Compare(handle: handles::Evaluation, other: Option<handles::Evaluation>) -> impl IntoView {
+    let style = style! {
+        div {
+            padding: 16px;
+        }
+        h2 {
+            font-size: 100%;
+            font-weight: 500;
+        }
+        ul {
+            padding-left: 16px;
+        }
+    };
+    let (error, comparison) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || Request::Evaluation(
+                handle.clone(),
+                requests::Evaluation::Compare(other.clone())
+            )),
+            |Response::EvaluationCompare(comparison)| comparison
+        )
+    };
+    let section = |title: &'static str, evaluation: handles::Evaluation, names: Vec<String>| {
+        (!names.is_empty()).then(|| {
+            view! {
+                <h2>{title} " (" {names.len()} ")"</h2>
+                <ul>
+                    {names
+                        .into_iter()
+                        .map(|name| {
+                            let href = Root::Evaluation(EvaluationPage {
+                                handle: evaluation.clone(),
+                                tab: EvaluationTab::Job {
+                                    handle: handles::Job {
+                                        evaluation: evaluation.clone(),
+                                        name: name.clone(),
+                                    },
+                                    log_tab: LogTab::default(),
+                                },
+                            });
+                            view! {
+                                <li>
+                                    <A href>{name}</A>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </ul>
+            }
+        })
+    };
+    view! { class=style,
+        <div>
+            <Trans error>
+                {move || {
+                    comparison()
+                        .map(|comparison| {
+                            let other = comparison.other.clone();
+                            let href = Root::Evaluation(EvaluationPage {
+                                handle: other.clone(),
+                                tab: EvaluationTab::Info,
+                            });
+                            let unchanged = comparison.added.is_empty()
+                                && comparison.removed.is_empty()
+                                && comparison.changed.is_empty();
+                            view! {
+                                <p>
+                                    "Compared with evaluation " <A href>
+                                        <UuidLabel uuid=other.uuid />
+                                    </A>
+                                </p>
+                                {section("Newly failing", handle.clone(), comparison.newly_failing)}
+                                {section("Fixed", handle.clone(), comparison.fixed)}
+                                {section("Added", handle.clone(), comparison.added)}
+                                {section("Removed", other, comparison.removed)}
+                                {section("Changed", handle.clone(), comparison.changed)}
+                                {unchanged.then(|| view! { <p>"No job changed."</p> })}
+                            }
+                        })
+                }}
+
+            </Trans>
+        </div>
+    }
+}
+
 #[component]
 fn Info(info: responses::EvaluationInfo) -> impl IntoView {
     let style = style! {
@@ -343,6 +635,65 @@ fn Info(info: responses::EvaluationInfo) -> impl IntoView {
                         </span>
                     </div>
                 </div>
+                {info
+                    .commit
+                    .map(|commit| {
+                        view! {
+                            <div class="field">
+                                <span class="label">Commit</span>
+                                <div class="value">
+                                    <span class="emph">{commit.subject}</span>
+                                    " by "
+                                    {commit.author}
+                                </div>
+                            </div>
+                        }
+                    })}
+                {info
+                    .lock
+                    .map(|lock| {
+                        view! {
+                            <div class="field">
+                                <span class="label">NAR hash</span>
+                                <input
+                                    class="value"
+                                    readonly
+                                    value=lock.nar_hash
+                                    style="width: 100%;"
+                                />
+                            </div>
+                            <div class="field">
+                                <span class="label">Flake inputs</span>
+                                <table class="value">
+                                    {lock
+                                        .inputs
+                                        .into_iter()
+                                        .map(|(name, input)| {
+                                            let modified = input
+                                                .last_modified
+                                                .and_then(|time| {
+                                                    time::OffsetDateTime::from_unix_timestamp(time).ok()
+                                                });
+                                            view! {
+                                                <tr>
+                                                    <td>{name}</td>
+                                                    <td>
+                                                        <code>{input.rev}</code>
+                                                    </td>
+                                                    <td>
+                                                        {modified
+                                                            .map(|datetime| {
+                                                                view! { <RelativeTime datetime /> }
+                                                            })}
+                                                    </td>
+                                                </tr>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()}
+                                </table>
+                            </div>
+                        }
+                    })}
             </div>
             <div class="block">
                 <div class="field">
@@ -367,6 +718,18 @@ fn Info(info: responses::EvaluationInfo) -> impl IntoView {
 
                     </div>
                 </div>
+                <div class="field">
+                    <span class="label">Outcome</span>
+                    <div class="value">
+                        <span class="emph">{info.outcome.to_string()}</span>
+                    </div>
+                </div>
+                <div class="field">
+                    <span class="label">Gate</span>
+                    <div class="value">
+                        <span class="emph">{info.gate.to_string()}</span>
+                    </div>
+                </div>
             </div>
         </div>
     }
@@ -445,6 +808,11 @@ fn Main(
                     .into_iter()
                     .map(|(name, info)| {
                         let last_run = info.last_run.clone();
+                        let label = match (&info.quarantine, info.required) {
+                            (Some(_), _) => format!("{name} (quarantined)"),
+                            (None, true) => format!("{name} (required)"),
+                            (None, false) => name,
+                        };
                         mk_item(
                             EvaluationTab::Job {
                                 handle: info.handle.clone(),
@@ -455,7 +823,7 @@ fn Main(
                                     TaskStatus::from(last_run.clone()).into()
                                 } />
                             },
-                            view! { <span>{name}</span> }.into_view(),
+                            view! { <span>{label}</span> }.into_view(),
                         )
                     })
                     .collect::<Vec<_>>()}
@@ -488,6 +856,13 @@ fn Main(
                         view! { <Icon icon=icondata::BiHomeAltRegular /> },
                         view! { Overview }.into_view(),
                     )}
+                    {mk_item(
+                        EvaluationTab::Compare {
+                            other: None,
+                        },
+                        view! { <Icon icon=icondata::BiGitCompareRegular /> },
+                        view! { Changes }.into_view(),
+                    )}
 
                 </ul>
             </section>
@@ -506,6 +881,13 @@ fn Main(
                                 </div>
                             }
                         }
+                        EvaluationTab::Compare { other } => {
+                            view! {
+                                <div>
+                                    <Compare handle=info.handle.clone() other />
+                                </div>
+                            }
+                        }
                         EvaluationTab::Job { handle, log_tab } => {
                             if let Some(job) = info
                                 .jobs
@@ -514,9 +896,13 @@ fn Main(
                                 .find(|info| info.handle == handle)
                                 .cloned()
                             {
+                                let jobset = handles::jobset((
+                                    info.project.name.clone(),
+                                    info.jobset_name.clone(),
+                                ));
                                 view! {
                                     <div class="term-theme">
-                                        <JobSubpage job log_tab />
+                                        <JobSubpage job jobset log_tab />
                                     </div>
                                 }
                             } else {
@@ -538,7 +924,7 @@ fn Main(
     let global_status: Signal<TaskStatus> = Signal::derive(move || {
         info.jobs
             .iter()
-            .map(|(_, info)| TaskStatus::from(info.last_run.clone()))
+            .map(|(_, info)| info.counted_status())
             .reduce(|a, b| a.union(&b))
             .unwrap_or_default()
     });
@@ -0,0 +1,126 @@
+use crate::prelude::*;
+use routes::{EvaluationPage, EvaluationTab, LogTab};
+
+#[component]
+fn JobHistoryEntry(entry: responses::JobHistoryEntry) -> impl IntoView {
+    let kind = data::TaskStatusKind::from(&entry.status);
+    let build_duration = entry.build_duration.map(time::Duration::seconds);
+    let href = Root::Evaluation(EvaluationPage {
+        handle: entry.handle.evaluation.clone(),
+        tab: EvaluationTab::Job {
+            handle: entry.handle.clone(),
+            log_tab: LogTab::default(),
+        },
+    });
+    view! {
+        <div class="row">
+            <div class="column">
+                <Status status=move || kind />
+                {" "}
+                {kind.to_string()}
+            </div>
+            <div class="column">
+                <A href>
+                    <RelativeTime datetime=entry.time_created />
+                </A>
+            </div>
+            <div class="column">
+                <Duration duration=Signal::derive(move || build_duration) />
+            </div>
+            <div class="column">{if entry.drv_changed { "changed" } else { "" }}</div>
+            <div class="column">
+                <code>{entry.drv}</code>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn JobHistory(
+    #[prop(into)] jobset: handles::Jobset,
+    #[prop(into)] job: String,
+    #[prop(into)] page: Signal<u32>,
+) -> impl IntoView {
+    let style = style! {
+        .rows :deep(.row), .header-columns {
+            display: grid;
+            grid-template-columns: 2fr 2fr 1fr 1fr 6fr;
+            gap: 10px;
+        }
+        .header-columns {
+            padding-top: 6px;
+            color: var(--color-fg-subtle);
+            font-size: var(--font-size-small);
+        }
+        .rows :deep(code) {
+            font-size: var(--font-size-small);
+            overflow-wrap: anywhere;
+        }
+    };
+    let limit = 20 as u8;
+    let (error, history) = {
+        let jobset = jobset.clone();
+        let job = job.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Jobset(
+                jobset.clone(),
+                requests::Jobset::JobHistory {
+                    job: job.clone(),
+                    offset: (page() - 1) * (limit as u32),
+                    limit,
+                }
+            )),
+            |responses::Response::JobsetJobHistory(history)| history
+        )
+    };
+    let count = Signal::derive(move || history().map(|history| history.total).unwrap_or(0));
+    let entries =
+        Signal::derive(move || history().map(|history| history.entries).unwrap_or_default());
+    let item_name = format!("{} ({})", job, jobset);
+    view! { class=style,
+        <PageHeader item_kind="Job history" item_name>
+            <span></span>
+        </PageHeader>
+        <Trans error>
+            <div class="is-table">
+                <div class="header">
+                    <div class="summary">{count}" evaluations"</div>
+                    <div class="header-columns">
+                        <div class="column">"Status"</div>
+                        <div class="column">"Evaluation"</div>
+                        <div class="column">"Build"</div>
+                        <div class="column">"Derivation"</div>
+                        <div class="column"></div>
+                    </div>
+                </div>
+                <div class="rows">
+                    <For
+                        each=entries
+                        key=|entry| {
+                            (entry.handle.clone(), data::TaskStatusKind::from(&entry.status))
+                        }
+                        children=move |entry| {
+                            view! { <JobHistoryEntry entry /> }
+                        }
+                    />
+
+                </div>
+            </div>
+            <Pagination
+                max={limit as u32}
+                count
+                current=page
+                link={
+                    let jobset = jobset.clone();
+                    let job = job.clone();
+                    move |page: u32| String::from(Root::JobHistory {
+                        jobset: jobset.clone(),
+                        job: job.clone(),
+                        page,
+                    })
+                }
+            />
+
+        </Trans>
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use routes::{EvaluationPage, EvaluationTab, LogTab};
 
 #[component]
 pub fn Jobset(
@@ -32,6 +33,60 @@ pub fn Jobset(
             |responses::Response::JobsetInfo(info)| info
         )
     };
+    let (error_flaky_jobs, flaky_jobs) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Jobset(
+                handle.clone(),
+                requests::Jobset::FlakyJobs
+            )),
+            |responses::Response::JobsetFlakyJobs(flaky_jobs)| flaky_jobs
+        )
+    };
+    let flaky_jobs = Signal::derive(move || flaky_jobs().unwrap_or_default());
+    let (error_quarantines, quarantines) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Jobset(
+                handle.clone(),
+                requests::Jobset::Quarantines
+            )),
+            |responses::Response::JobsetQuarantines(quarantines)| quarantines
+        )
+    };
+    let quarantines = Signal::derive(move || quarantines().unwrap_or_default());
+    let quarantine = request_action!(
+        QuarantineJob,
+        |project: String, jobset: String, job: String, reason: String, days: String| {
+            requests::Request::Jobset(
+                handles::Jobset {
+                    project: handles::Project { name: project },
+                    name: jobset,
+                },
+                requests::Jobset::Quarantine(requests::QuarantineDecl {
+                    job,
+                    reason,
+                    duration: days
+                        .trim()
+                        .parse::<u64>()
+                        .unwrap_or(1)
+                        .saturating_mul(86400),
+                }),
+            )
+        }
+    );
+    let unquarantine = request_action!(
+        UnquarantineJob,
+        |project: String, jobset: String, job: String| {
+            requests::Request::Jobset(
+                handles::Jobset {
+                    project: handles::Project { name: project },
+                    name: jobset,
+                },
+                requests::Jobset::Unquarantine(job),
+            )
+        }
+    );
     let limit = Signal::derive(move || 10 as u8);
     let offset = Signal::derive(move || (page() - 1) * (limit() as u32));
     let (error_evaluations, evaluations) = {
@@ -43,7 +98,9 @@ pub fn Jobset(
                 let handle = handle.clone();
                 move || {
                     requests::search::Kind::Evaluations(requests::search::Evaluation {
+                        bisection: None,
                         jobset_name: Some(handle.name.clone()),
+                        outcome: None,
                         project_name: Some(handle.project.name.clone()),
                         status: None,
                     })
@@ -77,14 +134,110 @@ pub fn Jobset(
                         .map(|info| {
                             view! {
                                 <table>
+                                    <tr>
+                                        <td>"Description"</td>
+                                        <td>{info.decl.description}</td>
+                                    </tr>
                                     <tr>
                                         <td>"URL"</td>
-                                        <td>{info.url}</td>
+                                        <td>{info.decl.url}</td>
                                     </tr>
                                     <tr>
                                         <td>"Flake"</td>
-                                        <td>{info.flake}</td>
+                                        <td>{info.decl.flake}</td>
+                                    </tr>
+                                    <tr>
+                                        <td>"Enabled"</td>
+                                        <td>{info.decl.enabled}</td>
+                                    </tr>
+                                    <tr>
+                                        <td>"Priority"</td>
+                                        <td>{info.decl.priority}</td>
                                     </tr>
+                                    {info
+                                        .decl
+                                        .keep
+                                        .map(|keep| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Kept evaluations"</td>
+                                                    <td>{keep}</td>
+                                                </tr>
+                                            }
+                                        })}
+                                    {info
+                                        .decl
+                                        .checked_jobs
+                                        .map(|pattern| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Checked jobs"</td>
+                                                    <td>{pattern}</td>
+                                                </tr>
+                                            }
+                                        })}
+                                    {info
+                                        .decl
+                                        .required_jobs
+                                        .map(|pattern| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Required jobs"</td>
+                                                    <td>{pattern}</td>
+                                                </tr>
+                                            }
+                                        })}
+                                    {info
+                                        .decl
+                                        .retry
+                                        .map(|retry| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Retries"</td>
+                                                    <td>
+                                                        {format!(
+                                                            "up to {} attempts, {}s backoff",
+                                                            retry.max_attempts,
+                                                            retry.backoff,
+                                                        )}
+                                                    </td>
+                                                </tr>
+                                            }
+                                        })}
+                                    <tr>
+                                        <td>"Kind"</td>
+                                        <td>
+                                            {match info.decl.kind {
+                                                requests::JobsetKind::Branch => "branch",
+                                                requests::JobsetKind::PullRequest => "pull request",
+                                            }}
+                                        </td>
+                                    </tr>
+                                    {info
+                                        .decl
+                                        .expiry
+                                        .map(|expiry| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Expiry"</td>
+                                                    <td>
+                                                        {format!("{} days of inactivity", expiry / 86400)}
+                                                    </td>
+                                                </tr>
+                                            }
+                                        })}
+                                    {info
+                                        .time_closed
+                                        .map(|datetime| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Closed"</td>
+                                                    <td>
+                                                        <RelativeTime datetime />
+                                                    </td>
+                                                </tr>
+                                            }
+                                        })}
                                 </table>
                             }
                         })
@@ -92,13 +245,133 @@ pub fn Jobset(
 
             </PageHeader>
         </Trans>
+        <Trans error=error_quarantines>
+            <Show when=move || user().is_some() || !quarantines().is_empty()>
+                <details>
+                    <summary>"Quarantines"</summary>
+                    <table>
+                        <For
+                            each=move || quarantines().into_iter().enumerate()
+                            key=|(i, _)| *i
+                            children=move |(_, quarantine)| {
+                                let active = quarantine.is_active(time::OffsetDateTime::now_utc());
+                                let job = quarantine.job.clone();
+                                view! {
+                                    <tr>
+                                        <td>{quarantine.job}</td>
+                                        <td>{quarantine.reason}</td>
+                                        <td>
+                                            <RelativeTime datetime=quarantine.time_created />
+                                        </td>
+                                        <td>
+                                            {match quarantine.time_lifted {
+                                                Some(datetime) => {
+                                                    view! {
+                                                        <>"lifted " <RelativeTime datetime /></>
+                                                    }
+                                                }
+                                                None => {
+                                                    view! {
+                                                        <>
+                                                            "until "
+                                                            <RelativeTime datetime=quarantine.time_expires />
+                                                        </>
+                                                    }
+                                                }
+                                            }}
+                                        </td>
+                                        <td>
+                                            <Show when=move || active && user().is_some()>
+                                                <ActionForm action=unquarantine>
+                                                    <input
+                                                        type="hidden"
+                                                        name="project"
+                                                        value=signal_handle().project.name
+                                                    />
+                                                    <input
+                                                        type="hidden"
+                                                        name="jobset"
+                                                        value=signal_handle().name
+                                                    />
+                                                    <input type="hidden" name="job" value=job.clone() />
+                                                    <input type="submit" value="Lift" />
+                                                </ActionForm>
+                                            </Show>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+
+                    </table>
+                    <Show when=move || user().is_some()>
+                        <ActionForm action=quarantine>
+                            <input
+                                type="hidden"
+                                name="project"
+                                value=signal_handle().project.name
+                            />
+                            <input type="hidden" name="jobset" value=signal_handle().name />
+                            <input type="text" name="job" placeholder="Job" />
+                            <input type="text" name="reason" placeholder="Reason" />
+                            <input type="number" name="days" value="7" min="1" />
+                            <input type="submit" value="Quarantine" />
+                        </ActionForm>
+                    </Show>
+                </details>
+            </Show>
+        </Trans>
+        <Trans error=error_flaky_jobs>
+            <Show when=move || !flaky_jobs().is_empty()>
+                <details>
+                    <summary>"Flaky jobs"</summary>
+                    <table>
+                        <For
+                            each=flaky_jobs
+                            key=|job| job.name.clone()
+                            children=move |job| {
+                                let href = String::from(Root::Evaluation(EvaluationPage {
+                                    handle: job.last.evaluation.clone(),
+                                    tab: EvaluationTab::Job {
+                                        handle: job.last.clone(),
+                                        log_tab: LogTab::default(),
+                                    },
+                                }));
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <A href>{job.name}</A>
+                                        </td>
+                                        <td>
+                                            {format!(
+                                                "flaky in {} of {} evaluations",
+                                                job.flaky,
+                                                job.total,
+                                            )}
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+
+                    </table>
+                </details>
+            </Show>
+        </Trans>
         <Trans error=error_evaluations>
             <Evaluations
                 count=evaluation_count
                 evaluations
                 buttons=Box::new(move || {
                     view! {
-                        <Show when=move || user().is_some()>
+                        <A href=Root::JobsetMatrix(signal_handle())>"Matrix"</A>
+                        <Show when=move || {
+                            user().is_some()
+                                && info()
+                                    .is_some_and(|info| {
+                                        info.time_closed.is_none() && info.decl.enabled
+                                    })
+                        }>
                             <ActionForm action>
                                 <input
                                     type="hidden"
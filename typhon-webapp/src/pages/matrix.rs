@@ -0,0 +1,133 @@
+use crate::prelude::*;
+use routes::{EvaluationPage, EvaluationTab, LogTab};
+
+#[component]
+fn MatrixCell(cell: Option<(handles::Run, responses::TaskStatus)>) -> impl IntoView {
+    cell.map(|(run, status)| {
+        let kind = data::TaskStatusKind::from(&status);
+        let href = Root::Evaluation(EvaluationPage {
+            handle: run.job.evaluation.clone(),
+            tab: EvaluationTab::Job {
+                handle: run.job,
+                log_tab: LogTab::default(),
+            },
+        });
+        view! {
+            <A href>
+                <Status status=move || kind />
+            </A>
+        }
+    })
+}
+
+#[component]
+pub fn JobsetMatrix(#[prop(into)] handle: handles::Jobset) -> impl IntoView {
+    let style = style! {
+        .matrix {
+            overflow-x: auto;
+            padding: 0 18px;
+        }
+        table {
+            border-collapse: collapse;
+        }
+        th, td {
+            padding: 4px 6px;
+            text-align: center;
+            font-weight: normal;
+        }
+        th {
+            color: var(--color-fg-subtle);
+            font-size: var(--font-size-small);
+        }
+        td.name {
+            text-align: left;
+            font-family: var(--font-family-monospace);
+        }
+        tr:hover {
+            background: var(--color-lllightgray);
+        }
+    };
+    let (error, matrix) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Jobset(
+                handle.clone(),
+                requests::Jobset::Matrix(10)
+            )),
+            |responses::Response::JobsetMatrix(matrix)| matrix
+        )
+    };
+    let item_name = handle.to_string();
+    let jobset = handle.clone();
+    view! { class=style,
+        <PageHeader item_kind="Jobset matrix" item_name>
+            <A href=Root::Jobset {
+                handle: handle.clone(),
+                page: 1,
+            }>"Evaluations"</A>
+        </PageHeader>
+        <Trans error>
+            {move || {
+                matrix()
+                    .map(|matrix| {
+                        view! {
+                            <div class="matrix">
+                                <table>
+                                    <tr>
+                                        <th></th>
+                                        {matrix
+                                            .evaluations
+                                            .into_iter()
+                                            .map(|evaluation| {
+                                                let href = Root::Evaluation(EvaluationPage {
+                                                    handle: evaluation.handle,
+                                                    tab: EvaluationTab::Info,
+                                                });
+                                                view! {
+                                                    <th title=evaluation.outcome.to_string()>
+                                                        <A href>
+                                                            <RelativeTime datetime=evaluation.time_created />
+                                                        </A>
+                                                    </th>
+                                                }
+                                            })
+                                            .collect::<Vec<_>>()}
+                                    </tr>
+                                    {matrix
+                                        .jobs
+                                        .into_iter()
+                                        .map(|job| {
+                                            let history = Root::JobHistory {
+                                                jobset: jobset.clone(),
+                                                job: job.name.clone(),
+                                                page: 1,
+                                            };
+                                            view! {
+                                                <tr>
+                                                    <td class="name">
+                                                        <A href=history>{job.name}</A>
+                                                    </td>
+                                                    {job
+                                                        .cells
+                                                        .into_iter()
+                                                        .map(|cell| {
+                                                            view! {
+                                                                <td>
+                                                                    <MatrixCell cell />
+                                                                </td>
+                                                            }
+                                                        })
+                                                        .collect::<Vec<_>>()}
+                                                </tr>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()}
+                                </table>
+                            </div>
+                        }
+                    })
+            }}
+
+        </Trans>
+    }
+}
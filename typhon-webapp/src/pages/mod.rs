@@ -1,15 +1,21 @@
 pub mod dashboard;
+pub mod deliveries;
 pub mod error;
 pub mod evaluation;
+pub mod job_history;
 pub mod jobset;
 pub mod login;
+pub mod matrix;
 pub mod project;
 pub mod projects;
 
 pub(crate) use dashboard::Dashboard;
+pub(crate) use deliveries::Deliveries;
 pub(crate) use error::*;
 pub(crate) use evaluation::Evaluation;
+pub(crate) use job_history::JobHistory;
 pub(crate) use jobset::Jobset;
 pub(crate) use login::Login;
+pub(crate) use matrix::JobsetMatrix;
 pub(crate) use project::Project;
 pub(crate) use projects::Projects;
@@ -19,6 +19,16 @@ fn Jobset(handle: handles::Jobset) -> impl IntoView {
                                         page: 1,
                                     }>{format!("{}", info.handle.clone().name)}</A>
                                 </div>
+                                <div class="column">{info.decl.description}</div>
+                                <div class="column">
+                                    {match info.decl.kind {
+                                        requests::JobsetKind::Branch => "",
+                                        requests::JobsetKind::PullRequest => "pull request",
+                                    }}
+                                </div>
+                                <div class="column">
+                                    {if info.decl.enabled { "" } else { "disabled" }}
+                                </div>
                             </div>
                         }
                     })}
@@ -28,6 +38,114 @@ fn Jobset(handle: handles::Jobset) -> impl IntoView {
     }
 }
 
+#[component]
+fn CustomActionForm(
+    project: String,
+    name: String,
+    custom_action: responses::CustomAction,
+) -> impl IntoView {
+    use serde_json::{Map, Value};
+
+    let run = request_action!(RunAction, |project: String, name: String, input: String| {
+        requests::Request::Project(
+            handles::Project { name: project },
+            requests::Project::RunAction {
+                name,
+                input: serde_json::from_str(&input).unwrap_or(Value::Null),
+            },
+        )
+    });
+
+    // one field per top-level property of the input schema
+    let fields: Vec<(String, Value, RwSignal<String>)> = custom_action
+        .schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.clone().into_iter().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, schema)| (key, schema, create_rw_signal(String::new())))
+        .collect();
+    let input = {
+        let fields = fields.clone();
+        move || {
+            let mut input = Map::new();
+            for (key, schema, value) in fields.iter() {
+                let value = value.get();
+                let value = match schema.get("type").and_then(Value::as_str) {
+                    Some("boolean") => Value::Bool(value == "true"),
+                    _ if value.is_empty() => continue,
+                    Some("integer") | Some("number") => {
+                        serde_json::from_str(&value).unwrap_or(Value::String(value))
+                    }
+                    _ => Value::String(value),
+                };
+                input.insert(key.clone(), value);
+            }
+            Value::Object(input).to_string()
+        }
+    };
+    let field = |(key, schema, value): (String, Value, RwSignal<String>)| {
+        let title = schema
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or(key.as_str())
+            .to_string();
+        let options = schema.get("enum").and_then(Value::as_array).cloned();
+        let input = match (schema.get("type").and_then(Value::as_str), options) {
+            (_, Some(options)) => view! {
+                <select on:change=move |ev| value.set(event_target_value(&ev))>
+                    <option value=""></option>
+                    {options
+                        .into_iter()
+                        .map(|option| {
+                            let option = match option {
+                                Value::String(option) => option,
+                                option => option.to_string(),
+                            };
+                            view! { <option value=option.clone()>{option}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            }
+            .into_view(),
+            (Some("boolean"), _) => view! {
+                <input
+                    type="checkbox"
+                    on:change=move |ev| {
+                        value.set(event_target_checked(&ev).to_string())
+                    }
+                />
+            }
+            .into_view(),
+            (Some("integer") | Some("number"), _) => view! {
+                <input type="number" on:input=move |ev| value.set(event_target_value(&ev)) />
+            }
+            .into_view(),
+            _ => view! {
+                <input type="text" on:input=move |ev| value.set(event_target_value(&ev)) />
+            }
+            .into_view(),
+        };
+        view! {
+            <label>
+                {title}
+                {input}
+            </label>
+        }
+    };
+    view! {
+        <ActionForm action=run>
+            <input type="hidden" name="project" value=project />
+            <input type="hidden" name="name" value=name.clone() />
+            <input type="hidden" name="input" prop:value=input />
+            <span title=custom_action.description>{name.clone()}</span>
+            {fields.into_iter().map(field).collect::<Vec<_>>()}
+            <input type="submit" value="Run" />
+        </ActionForm>
+    }
+}
+
 #[component]
 pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
     let user: Signal<Option<data::User>> = use_context().unwrap();
@@ -54,6 +172,51 @@ pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
                 .collect::<Vec<_>>()
         })
     };
+    let hidden_jobsets = {
+        let handle = handle.clone();
+        Signal::derive(move || {
+            info()
+                .map(|x| x.hidden_jobsets)
+                .unwrap_or(Vec::new())
+                .into_iter()
+                .map(|name| handles::Jobset {
+                    project: handle.clone(),
+                    name,
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    let closed_jobsets = {
+        let handle = handle.clone();
+        Signal::derive(move || {
+            info()
+                .map(|x| x.closed_jobsets)
+                .unwrap_or(Vec::new())
+                .into_iter()
+                .map(|name| handles::Jobset {
+                    project: handle.clone(),
+                    name,
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    let (error_non_deterministic_builds, non_deterministic_builds) = {
+        let handle = handle.clone();
+        resource!(
+            Signal::derive(move || requests::Request::Project(
+                handle.clone(),
+                requests::Project::NonDeterministicBuilds
+            )),
+            |responses::Response::ProjectNonDeterministicBuilds(builds)| builds
+        )
+    };
+    let non_deterministic_drvs = Signal::derive(move || {
+        non_deterministic_builds()
+            .unwrap_or(Vec::new())
+            .into_iter()
+            .map(|build| build.drv)
+            .collect::<Vec<_>>()
+    });
     let update_jobsets = request_action!(UpdateJobsets, |name: String| requests::Request::Project(
         handles::Project { name },
         requests::Project::UpdateJobsets,
@@ -91,6 +254,37 @@ pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
                                         <td>"Public key"</td>
                                         <td>{info.public_key}</td>
                                     </tr>
+                                    <tr>
+                                        <td>"Webhook signatures"</td>
+                                        <td>
+                                            {if info.has_webhook_secret {
+                                                "verified"
+                                            } else {
+                                                "not verified"
+                                            }}
+                                        </td>
+                                    </tr>
+                                    <tr>
+                                        <td>"Commit statuses"</td>
+                                        <td>
+                                            {match info.forge {
+                                                Some(forge) => {
+                                                    format!("{} ({})", forge.repository, forge.kind)
+                                                }
+                                                None => "not reported".to_string(),
+                                            }}
+                                        </td>
+                                    </tr>
+                                    {info
+                                        .previous_public_key
+                                        .map(|key| {
+                                            view! {
+                                                <tr>
+                                                    <td>"Previous public key"</td>
+                                                    <td>{key}</td>
+                                                </tr>
+                                            }
+                                        })}
                                 </table>
                             }
                         })
@@ -109,6 +303,10 @@ pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
                             <input type="submit" value="Refresh" />
 
                         </ActionForm>
+                        <A href=Root::Deliveries {
+                            project: handle.clone(),
+                            page: 1,
+                        }>"Webhook deliveries"</A>
                     </Show>
                     {move || {
                         info()
@@ -121,6 +319,12 @@ pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
                             })
                             .flatten()
                     }}
+                    {move || {
+                        info()
+                            .map(|info| info.last_refresh_failure)
+                            .flatten()
+                            .map(|failure| view! { <span>{format!("{}", failure)}</span> })
+                    }}
 
                     <div class="header-columns"></div>
                 </div>
@@ -136,6 +340,75 @@ pub(crate) fn Project(handle: handles::Project) -> impl IntoView {
                 </div>
 
             </div>
+            <Show when=move || user().is_some() && !hidden_jobsets().is_empty()>
+                <details>
+                    <summary>"Hidden jobsets"</summary>
+                    <div class="rows">
+                        <For
+                            each=hidden_jobsets
+                            key=|handle| handle.name.clone()
+                            children=move |handle| {
+                                view! { <Jobset handle /> }
+                            }
+                        />
+
+                    </div>
+                </details>
+            </Show>
+            <Show when=move || !closed_jobsets().is_empty()>
+                <details>
+                    <summary>"Closed jobsets"</summary>
+                    <div class="rows">
+                        <For
+                            each=closed_jobsets
+                            key=|handle| handle.name.clone()
+                            children=move |handle| {
+                                view! { <Jobset handle /> }
+                            }
+                        />
+
+                    </div>
+                </details>
+            </Show>
+            <Trans error=error_non_deterministic_builds>
+                <Show when=move || !non_deterministic_drvs().is_empty()>
+                    <details>
+                        <summary>"Non-deterministic derivations"</summary>
+                        <div class="rows">
+                            <For
+                                each=non_deterministic_drvs
+                                key=|drv| drv.clone()
+                                children=move |drv| {
+                                    view! { <div>{drv}</div> }
+                                }
+                            />
+
+                        </div>
+                    </details>
+                </Show>
+            </Trans>
+            <Show when=move || { user().is_some() }>
+                <div class="custom-actions">
+                    {move || {
+                        info()
+                            .map(|info| {
+                                info.custom_actions
+                                    .into_iter()
+                                    .map(|(name, custom_action)| {
+                                        view! {
+                                            <CustomActionForm
+                                                project=info.handle.name.clone()
+                                                name
+                                                custom_action
+                                            />
+                                        }
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                    }}
+
+                </div>
+            </Show>
             <ul></ul>
 
         // FIXME: forms need to be in the transition component or else there are hydration bugs
@@ -14,10 +14,20 @@ pub enum Root<MODE: SubpageInformation = Full> {
     },
     Projects,
     Project(handles::Project),
+    Deliveries {
+        project: handles::Project,
+        page: MODE::PageNum,
+    },
     Jobset {
         handle: handles::Jobset,
         page: MODE::PageNum,
     },
+    JobHistory {
+        jobset: handles::Jobset,
+        job: String,
+        page: MODE::PageNum,
+    },
+    JobsetMatrix(handles::Jobset),
     Evaluation(EvaluationPage<MODE>),
 }
 pub trait SubpageInformation: Copy + Clone + Debug + Eq {
@@ -77,7 +87,14 @@ impl From<Root> for Root<Empty> {
             Root::Dashboard { tab, page } => Root::Dashboard { tab, page },
             Root::Projects => Root::Projects,
             Root::Project(h) => Root::Project(h),
+            Root::Deliveries { project, .. } => Root::Deliveries { project, page: () },
             Root::Jobset { handle, .. } => Root::Jobset { handle, page: () },
+            Root::JobHistory { jobset, job, .. } => Root::JobHistory {
+                jobset,
+                job,
+                page: (),
+            },
+            Root::JobsetMatrix(h) => Root::JobsetMatrix(h),
             Root::Evaluation(e) => Root::Evaluation(e.into()),
         }
     }
@@ -90,7 +107,14 @@ impl From<Root<Empty>> for Root {
             Root::Dashboard { tab, page } => Root::Dashboard { tab, page },
             Root::Projects => Root::Projects,
             Root::Project(h) => Root::Project(h),
+            Root::Deliveries { project, .. } => Root::Deliveries { project, page: 1 },
             Root::Jobset { handle, .. } => Root::Jobset { handle, page: 1 },
+            Root::JobHistory { jobset, job, .. } => Root::JobHistory {
+                jobset,
+                job,
+                page: 1,
+            },
+            Root::JobsetMatrix(h) => Root::JobsetMatrix(h),
             Root::Evaluation(e) => Root::Evaluation(e.into()),
         }
     }
@@ -103,7 +127,10 @@ impl From<Root<Empty>> for Option<handles::Handle> {
             Root::Dashboard { .. } => None?,
             Root::Projects => None?,
             Root::Project(handle) => handles::Handle::Project(handle),
+            Root::Deliveries { project, .. } => handles::Handle::Project(project),
             Root::Jobset { handle, .. } => handles::Handle::Jobset(handle),
+            Root::JobHistory { jobset, .. } => handles::Handle::Jobset(jobset),
+            Root::JobsetMatrix(handle) => handles::Handle::Jobset(handle),
             Root::Evaluation(eval) => handles::Handle::Evaluation(eval.handle),
         })
     }
@@ -168,6 +195,10 @@ pub enum DashboardTab {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EvaluationTab {
     Info,
+    /// The differences with another evaluation, by default the previous one
+    Compare {
+        other: Option<handles::Evaluation>,
+    },
     Job {
         handle: handles::Job,
         log_tab: LogTab,
@@ -228,6 +259,17 @@ impl TryFrom<Location> for Root {
                     Self::Dashboard { tab, page }
                 }
                 ["project", project] => Self::Project(handles::project(project.to_string())),
+                ["project", project, "deliveries"] => {
+                    let project = handles::project(project.to_string());
+                    let page = query()
+                        .get("page")
+                        .and_then(|p| p.parse::<u32>().ok())
+                        .unwrap_or(1);
+                    if page < 1 {
+                        Err(r)?
+                    }
+                    Self::Deliveries { project, page }
+                }
                 ["project", project, "jobset", jobset] => {
                     let project = project.to_string();
                     let jobset = jobset.to_string();
@@ -241,6 +283,21 @@ impl TryFrom<Location> for Root {
                     }
                     Self::Jobset { handle, page }
                 }
+                ["project", project, "jobset", jobset, "matrix"] => {
+                    Self::JobsetMatrix(handles::jobset((project.to_string(), jobset.to_string())))
+                }
+                ["project", project, "jobset", jobset, "job", job] => {
+                    let jobset = handles::jobset((project.to_string(), jobset.to_string()));
+                    let job = job.to_string();
+                    let page = query()
+                        .get("page")
+                        .and_then(|p| p.parse::<u32>().ok())
+                        .unwrap_or(1);
+                    if page < 1 {
+                        Err(r)?
+                    }
+                    Self::JobHistory { jobset, job, page }
+                }
                 ["evaluation", uuid, rest @ ..] if let Ok(uuid) = uuid::Uuid::from_str(uuid) => {
                     let handle = handles::evaluation(uuid);
                     let tab = match rest {
@@ -259,7 +316,18 @@ impl TryFrom<Location> for Root {
                             };
                             EvaluationTab::Job { handle, log_tab }
                         }
-                        [] => EvaluationTab::Info,
+                        [] => match query().get("compare") {
+                            Some(other) if other.is_empty() => {
+                                EvaluationTab::Compare { other: None }
+                            }
+                            Some(other) => match uuid::Uuid::from_str(&other) {
+                                Ok(uuid) => EvaluationTab::Compare {
+                                    other: Some(handles::evaluation(uuid)),
+                                },
+                                Err(_) => Err(r)?,
+                            },
+                            None => EvaluationTab::Info,
+                        },
                     };
                     Self::Evaluation(EvaluationPage { handle, tab })
                 }
@@ -277,11 +345,25 @@ impl From<Root> for String {
             Root::Dashboard { tab, page } => format!("/dashboard/{}?page={page}", tab),
             Root::Projects => "".to_string(),
             Root::Project(handle) => format!("/project/{}", encode(&handle.name)),
+            Root::Deliveries { project, page } => {
+                format!("/project/{}/deliveries?page={page}", encode(&project.name),)
+            }
             Root::Jobset { handle, page } => format!(
                 "/project/{}/jobset/{}?page={page}",
                 encode(&handle.project.name),
                 encode(&handle.name),
             ),
+            Root::JobHistory { jobset, job, page } => format!(
+                "/project/{}/jobset/{}/job/{}?page={page}",
+                encode(&jobset.project.name),
+                encode(&jobset.name),
+                encode(&job),
+            ),
+            Root::JobsetMatrix(handle) => format!(
+                "/project/{}/jobset/{}/matrix",
+                encode(&handle.project.name),
+                encode(&handle.name),
+            ),
             Root::Evaluation(e) => format!(
                 "/evaluation/{}/{}",
                 e.handle.uuid,
@@ -290,6 +372,12 @@ impl From<Root> for String {
                         let log_tab: &str = log_tab.into();
                         format!("{}/{}", encode(&handle.name), log_tab)
                     }
+                    EvaluationTab::Compare { other } => format!(
+                        "?compare={}",
+                        other
+                            .map(|other| other.uuid.to_string())
+                            .unwrap_or_default()
+                    ),
                     EvaluationTab::Info => "".into(),
                 }
             ),
@@ -313,6 +401,13 @@ pub fn Router() -> impl IntoView {
         Ok(Root::Project(handle)) => {
             view! { <Project handle /> }
         }
+        Ok(Root::Deliveries { project, .. }) => {
+            let page = create_memo(move |_| match page() {
+                Ok(Root::Deliveries { page, .. }) => page,
+                _ => 1,
+            });
+            view! { <Deliveries project page /> }
+        }
         Ok(Root::Jobset { handle, .. }) => {
             let page = create_memo(move |_| match page() {
                 Ok(Root::Jobset { page, .. }) => page,
@@ -320,6 +415,16 @@ pub fn Router() -> impl IntoView {
             });
             view! { <Jobset handle page /> }
         }
+        Ok(Root::JobHistory { jobset, job, .. }) => {
+            let page = create_memo(move |_| match page() {
+                Ok(Root::JobHistory { page, .. }) => page,
+                _ => 1,
+            });
+            view! { <JobHistory jobset job page /> }
+        }
+        Ok(Root::JobsetMatrix(handle)) => {
+            view! { <JobsetMatrix handle /> }
+        }
         Ok(Root::Evaluation(e)) => {
             let handle = Signal::derive(move || e.handle.clone());
             let tab = create_memo(move |_| match page() {
@@ -2,6 +2,7 @@ use typhon_core::error;
 use typhon_core::handle_request;
 use typhon_core::User;
 use typhon_core::EVENT_LOGGER;
+use typhon_types::data::EvaluationOutcome;
 use typhon_types::handles;
 use typhon_types::requests::*;
 use typhon_types::responses::{Response, ResponseError};
@@ -43,13 +44,28 @@ impl Responder for ResponseWrapper {
             Ok => web::Json(true).respond_to(req),
             Search(payload) => web::Json(payload).respond_to(req),
             ProjectInfo(payload) => web::Json(payload).respond_to(req),
+            ProjectNonDeterministicBuilds(payload) => web::Json(payload).respond_to(req),
+            ProjectEncryptSecrets(payload) => payload.respond_to(req).map_into_left_body(),
+            ProjectRunAction(payload) => web::Json(payload).respond_to(req),
             JobsetInfo(payload) => web::Json(payload).respond_to(req),
+            JobsetBisection(payload) => web::Json(payload).respond_to(req),
             JobsetEvaluate(payload) => web::Json(payload).respond_to(req),
+            JobsetFlakyJobs(payload) => web::Json(payload).respond_to(req),
+            JobsetJobHistory(payload) => web::Json(payload).respond_to(req),
+            JobsetLatest(payload) => web::Json(payload).respond_to(req),
+            JobsetLatestJob(payload) => web::Json(payload).respond_to(req),
+            JobsetMatrix(payload) => web::Json(payload).respond_to(req),
+            JobsetQuarantines(payload) => web::Json(payload).respond_to(req),
+            EvaluationCompare(payload) => web::Json(payload).respond_to(req),
+            EvaluationGate(payload) => web::Json(payload).respond_to(req),
             EvaluationInfo(payload) => web::Json(payload).respond_to(req),
+            EvaluationRerunFailed(payload) => web::Json(payload).respond_to(req),
             JobInfo(payload) => web::Json(payload).respond_to(req),
             BuildInfo(payload) => web::Json(payload).respond_to(req),
             ActionInfo(payload) => web::Json(payload).respond_to(req),
             RunInfo(payload) => web::Json(payload).respond_to(req),
+            DeliveryInfo(payload) => web::Json(payload).respond_to(req),
+            DeliveryReplay(payload) => web::Json(payload).respond_to(req),
             User(payload) => web::Json(payload).respond_to(req),
         }
     }
@@ -128,6 +144,12 @@ r!(
             Project::Info,
         );
 
+    project_non_deterministic_builds(path: web::Path<String>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::NonDeterministicBuilds,
+        );
+
     project_refresh(path: web::Path<String>) =>
         Request::Project(
             handles::project(path.into_inner()),
@@ -140,36 +162,185 @@ r!(
             Project::SetDecl(body.into_inner()),
         );
 
+    project_rotate_key(path: web::Path<String>, body: web::Json<u64>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::RotateKey { grace_period: body.into_inner() },
+        );
+
+    project_encrypt_secrets(path: web::Path<String>, body: web::Json<serde_json::Value>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::EncryptSecrets(body.into_inner()),
+        );
+
+    project_set_sandbox(path: web::Path<String>, body: web::Json<SandboxPolicy>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::SetSandbox(body.into_inner()),
+        );
+
+    project_run_action(path: web::Path<(String,String)>, body: web::Json<serde_json::Value>) => {
+        let (project, name) = path.into_inner();
+        Request::Project(
+            handles::project(project),
+            Project::RunAction { name, input: body.into_inner() },
+        )
+    };
+
+    project_set_webhook_secret(path: web::Path<String>, body: web::Json<Option<String>>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::SetWebhookSecret(body.into_inner()),
+        );
+
+    project_set_forge(path: web::Path<String>, body: web::Json<Option<ForgeSettings>>) =>
+        Request::Project(
+            handles::project(path.into_inner()),
+            Project::SetForge(body.into_inner()),
+        );
+
     project_update_jobsets(path: web::Path<String>) =>
         Request::Project(
             handles::project(path.into_inner()),
             Project::UpdateJobsets,
         );
 
+    jobset_bisect(path: web::Path<(String,String,String)>) =>
+        {
+            let (project, jobset, job) = path.into_inner();
+            Request::Jobset(
+                handles::jobset((project, jobset)),
+                Jobset::Bisect(job),
+            )
+        };
+
+    jobset_bisection(path: web::Path<(String,String,String)>) =>
+        {
+            let (project, jobset, job) = path.into_inner();
+            Request::Jobset(
+                handles::jobset((project, jobset)),
+                Jobset::Bisection(job),
+            )
+        };
+
     jobset_evaluate(path: web::Path<(String,String)>) =>
         Request::Jobset(
             handles::jobset(path.into_inner()),
             Jobset::Evaluate(true),
         );
 
+    jobset_flaky_jobs(path: web::Path<(String,String)>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::FlakyJobs,
+        );
+
     jobset_info(path: web::Path<(String,String)>) =>
         Request::Jobset(
             handles::jobset(path.into_inner()),
             Jobset::Info,
         );
 
+    jobset_job_history(path: web::Path<(String,String,String)>, query: web::Query<HashMap<String,String>>) =>
+        {
+            let (project, jobset, job) = path.into_inner();
+            Request::Jobset(
+                handles::jobset((project, jobset)),
+                Jobset::JobHistory {
+                    job,
+                    offset: query.get("offset").and_then(|offset| offset.parse().ok()).unwrap_or(0),
+                    limit: query.get("limit").and_then(|limit| limit.parse().ok()).unwrap_or(20),
+                },
+            )
+        };
+
+    jobset_latest(path: web::Path<(String,String)>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::Latest,
+        );
+
+    jobset_latest_job(path: web::Path<(String,String,String)>) =>
+        {
+            let (project, jobset, job) = path.into_inner();
+            Request::Jobset(
+                handles::jobset((project, jobset)),
+                Jobset::LatestJob(job),
+            )
+        };
+
+    jobset_matrix(path: web::Path<(String,String)>, query: web::Query<HashMap<String,String>>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::Matrix(
+                query.get("evaluations").and_then(|count| count.parse().ok()).unwrap_or(10),
+            ),
+        );
+
+    jobset_quarantine(path: web::Path<(String,String)>, body: web::Json<QuarantineDecl>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::Quarantine(body.into_inner()),
+        );
+
+    jobset_quarantines(path: web::Path<(String,String)>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::Quarantines,
+        );
+
+    jobset_set_decl(path: web::Path<(String,String)>, body: web::Json<JobsetDecl>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::SetDecl(body.into_inner()),
+        );
+
+    jobset_unquarantine(path: web::Path<(String,String)>, body: web::Json<String>) =>
+        Request::Jobset(
+            handles::jobset(path.into_inner()),
+            Jobset::Unquarantine(body.into_inner()),
+        );
+
     evaluation_cancel(path: web::Path<Uuid>) =>
         Request::Evaluation(
             handles::evaluation(path.into_inner()),
             Evaluation::Cancel,
         );
 
+    evaluation_compare(path: web::Path<Uuid>) =>
+        Request::Evaluation(
+            handles::evaluation(path.into_inner()),
+            Evaluation::Compare(None),
+        );
+
+    evaluation_compare_with(path: web::Path<(Uuid,Uuid)>) =>
+        {
+            let (evaluation, other) = path.into_inner();
+            Request::Evaluation(
+                handles::evaluation(evaluation),
+                Evaluation::Compare(Some(handles::evaluation(other))),
+            )
+        };
+
+    evaluation_gate_info(path: web::Path<Uuid>) =>
+        Request::Evaluation(
+            handles::evaluation(path.into_inner()),
+            Evaluation::Gate,
+        );
+
     evaluation_info(path: web::Path<Uuid>) =>
         Request::Evaluation(
             handles::evaluation(path.into_inner()),
             Evaluation::Info,
         );
 
+    evaluation_rerun_failed(path: web::Path<Uuid>, body: web::Json<RerunFilter>) =>
+        Request::Evaluation(
+            handles::evaluation(path.into_inner()),
+            Evaluation::RerunFailed(body.into_inner()),
+        );
+
     job_info(path: web::Path<(Uuid,String)>) =>
         Request::Job(
             handles::job(path.into_inner()),
@@ -188,6 +359,18 @@ r!(
             Run::Info,
         );
 
+    run_rerun_begin(path: web::Path<(Uuid,String,u32)>) =>
+        Request::Run(
+            handles::run(path.into_inner()),
+            Run::RerunBegin,
+        );
+
+    run_rerun_end(path: web::Path<(Uuid,String,u32)>) =>
+        Request::Run(
+            handles::run(path.into_inner()),
+            Run::RerunEnd,
+        );
+
     job_rerun(path: web::Path<(Uuid,String)>) =>
         Request::Job(
             handles::job(path.into_inner()),
@@ -206,6 +389,18 @@ r!(
             Action::Info,
         );
 
+    delivery_info(path: web::Path<Uuid>) =>
+        Request::Delivery(
+            handles::delivery(path.into_inner()),
+            Delivery::Info,
+        );
+
+    delivery_replay(path: web::Path<Uuid>) =>
+        Request::Delivery(
+            handles::delivery(path.into_inner()),
+            Delivery::Replay,
+        );
+
     login(body: web::Json<String>) =>
         Request::Login { password: body.into_inner() };
 );
@@ -233,6 +428,111 @@ async fn dist(
     }
 }
 
+/// Redirects to a file of the latest successful instance of a job
+async fn jobset_latest_dist(
+    user: UserWrapper,
+    path: web::Path<(String, String, String, String)>,
+) -> Result<HttpResponse, ResponseErrorWrapper> {
+    let (project, jobset, job, path) = path.into_inner();
+    let req = Request::Jobset(handles::jobset((project, jobset)), Jobset::LatestJob(job));
+    let handle = match handle_request(user.0, req)
+        .await
+        .map_err(ResponseErrorWrapper)?
+    {
+        Response::JobsetLatestJob(handle) => handle,
+        _ => return Err(ResponseErrorWrapper(ResponseError::InternalError)),
+    };
+    // the job name and the path were decoded from the request
+    let path = path
+        .split('/')
+        .map(urlencoding::encode)
+        .collect::<Vec<_>>()
+        .join("/");
+    Ok(HttpResponse::Found()
+        .insert_header((
+            "Location",
+            format!(
+                "/api/evaluations/{}/jobs/{}/dist/{}",
+                handle.evaluation.uuid,
+                urlencoding::encode(&handle.name),
+                path
+            ),
+        ))
+        .finish())
+}
+
+/// An SVG badge showing the gate of an evaluation, if any
+fn badge(gate: Option<EvaluationOutcome>) -> HttpResponse {
+    let (label, color) = match gate {
+        Some(EvaluationOutcome::Pass) => ("pass", "#2da44e"),
+        Some(EvaluationOutcome::Fail) => ("fail", "#cf222e"),
+        Some(EvaluationOutcome::Pending) => ("pending", "#bf8700"),
+        None => ("unknown", "#8c959f"),
+    };
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="110" height="20" role="img" aria-label="typhon: {label}"><rect width="50" height="20" fill="#555"/><rect x="50" width="60" height="20" fill="{color}"/><g fill="#fff" text-anchor="middle" font-family="Verdana,sans-serif" font-size="11"><text x="25" y="14">typhon</text><text x="80" y="14">{label}</text></g></svg>"##
+    );
+    HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .insert_header(("Cache-Control", "no-cache"))
+        .body(svg)
+}
+
+async fn evaluation_gate(
+    user: &UserWrapper,
+    evaluation: Uuid,
+) -> Result<EvaluationOutcome, ResponseErrorWrapper> {
+    let req = Request::Evaluation(handles::evaluation(evaluation), Evaluation::Gate);
+    match handle_request(user.0, req)
+        .await
+        .map_err(ResponseErrorWrapper)?
+    {
+        Response::EvaluationGate(gate) => Ok(gate),
+        _ => Err(ResponseErrorWrapper(ResponseError::InternalError)),
+    }
+}
+
+async fn evaluation_badge(
+    user: UserWrapper,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ResponseErrorWrapper> {
+    let gate = evaluation_gate(&user, path.into_inner()).await?;
+    Ok(badge(Some(gate)))
+}
+
+/// The badge of the latest evaluation of a jobset
+async fn jobset_badge(
+    user: UserWrapper,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ResponseErrorWrapper> {
+    let (project, jobset) = path.into_inner();
+    let req = Request::Search(search::Request {
+        limit: 1,
+        offset: 0,
+        kind: search::Kind::Evaluations(search::Evaluation {
+            bisection: Some(false),
+            jobset_name: Some(jobset),
+            project_name: Some(project),
+            ..Default::default()
+        }),
+    });
+    let latest = match handle_request(user.0, req)
+        .await
+        .map_err(ResponseErrorWrapper)?
+    {
+        Response::Search(typhon_types::responses::search::Info {
+            results: typhon_types::responses::search::Results::Evaluations(evaluations),
+            ..
+        }) => evaluations.into_iter().next(),
+        _ => return Err(ResponseErrorWrapper(ResponseError::InternalError)),
+    };
+    let gate = match latest {
+        Some(evaluation) => Some(evaluation_gate(&user, evaluation.uuid).await?),
+        None => None,
+    };
+    Ok(badge(gate))
+}
+
 fn streaming_response(
     stream: impl futures_core::stream::Stream<Item = String> + 'static,
 ) -> HttpResponse {
@@ -287,11 +587,92 @@ async fn events() -> Option<HttpResponse> {
     })
 }
 
+mod webhook_signature {
+    use actix_web::http::header::HeaderMap;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    #[derive(Debug, derive_more::Display)]
+    pub enum Error {
+        #[display("malformed signature header")]
+        Malformed,
+        #[display("signature mismatch")]
+        Mismatch,
+        #[display("no signature header")]
+        Missing,
+    }
+
+    fn header<'a>(headers: &'a HeaderMap, name: &str) -> Result<Option<&'a str>, Error> {
+        headers
+            .get(name)
+            .map(|value| value.to_str().map_err(|_| Error::Malformed))
+            .transpose()
+    }
+
+    /// Checks a hex-encoded HMAC-SHA256 signature of the body
+    fn check_hmac(secret: &str, body: &[u8], signature: &str) -> Result<(), Error> {
+        let signature = hex::decode(signature).map_err(|_| Error::Malformed)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(body);
+        mac.verify_slice(&signature).map_err(|_| Error::Mismatch)
+    }
+
+    /// Compares a plain token to the secret in constant time
+    fn check_token(secret: &str, token: &str) -> Result<(), Error> {
+        let (secret, token) = (secret.as_bytes(), token.as_bytes());
+        let diff = secret
+            .iter()
+            .zip(token)
+            .fold(secret.len() ^ token.len(), |acc, (a, b)| {
+                acc | (a ^ b) as usize
+            });
+        if diff == 0 {
+            Ok(())
+        } else {
+            Err(Error::Mismatch)
+        }
+    }
+
+    /// Verifies that a delivery was sent by a forge knowing the secret: GitHub
+    /// and Gitea (or its forks) sign the body, GitLab sends the secret as is.
+    pub fn verify(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), Error> {
+        if let Some(signature) = header(headers, "x-hub-signature-256")? {
+            let signature = signature.strip_prefix("sha256=").ok_or(Error::Malformed)?;
+            return check_hmac(secret, body, signature);
+        }
+        for name in [
+            "x-gitea-signature",
+            "x-forgejo-signature",
+            "x-gogs-signature",
+        ] {
+            if let Some(signature) = header(headers, name)? {
+                return check_hmac(secret, body, signature);
+            }
+        }
+        if let Some(token) = header(headers, "x-gitlab-token")? {
+            return check_token(secret, token);
+        }
+        Err(Error::Missing)
+    }
+}
+
 async fn webhook(
     path: web::Path<String>,
     req: HttpRequest,
     body: String,
 ) -> Result<HttpResponse, ResponseErrorWrapper> {
+    let handle = handles::project(path.into_inner());
+    let secret = {
+        let handle = handle.clone();
+        web::block(move || typhon_core::webhook_secret(&handle)).await??
+    };
+    if let Some(secret) = secret {
+        if let Err(e) = webhook_signature::verify(&secret, req.headers(), body.as_bytes()) {
+            tracing::warn!("rejected webhook delivery for project {}: {}", handle, e);
+            return Ok(HttpResponse::Unauthorized().finish());
+        }
+    }
     let input = typhon_core::webhooks::Input {
         headers: req
             .headers()
@@ -311,9 +692,8 @@ async fn webhook(
             .collect::<Result<HashMap<_, _>, ResponseErrorWrapper>>()?,
         body,
     };
-    let handle = handles::project(path.into_inner());
-    web::block(move || typhon_core::webhook(handle, input)).await??;
-    Ok(HttpResponse::Ok().finish())
+    let delivery = web::block(move || typhon_core::webhook(handle, input)).await??;
+    Ok(HttpResponse::Accepted().json(delivery))
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -333,20 +713,54 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                     .route("", web::get().to(project_info))
                     .route("/create", web::post().to(create_project))
                     //.route("/delete", web::post().to(project_delete))
+                    .route(
+                        "/non_deterministic_builds",
+                        web::get().to(project_non_deterministic_builds),
+                    )
                     .route("/refresh", web::post().to(project_refresh))
                     .route("/update_jobsets", web::post().to(project_update_jobsets))
                     .route("/set_decl", web::post().to(project_set_decl))
+                    .route("/rotate_key", web::post().to(project_rotate_key))
+                    .route("/encrypt_secrets", web::post().to(project_encrypt_secrets))
+                    .route("/set_sandbox", web::post().to(project_set_sandbox))
+                    .route(
+                        "/set_webhook_secret",
+                        web::post().to(project_set_webhook_secret),
+                    )
+                    .route("/set_forge", web::post().to(project_set_forge))
+                    .route("/actions/{action}/run", web::post().to(project_run_action))
                     .route("/webhook", web::post().to(webhook))
                     .service(
                         web::scope("/jobsets/{jobset}")
                             .route("", web::get().to(jobset_info))
-                            .route("/evaluate", web::post().to(jobset_evaluate)),
+                            .route("/badge.svg", web::get().to(jobset_badge))
+                            .route("/evaluate", web::post().to(jobset_evaluate))
+                            .route("/flaky_jobs", web::get().to(jobset_flaky_jobs))
+                            .route("/jobs/{job}/bisect", web::post().to(jobset_bisect))
+                            .route("/jobs/{job}/bisection", web::get().to(jobset_bisection))
+                            .route("/jobs/{job}/history", web::get().to(jobset_job_history))
+                            .route("/latest", web::get().to(jobset_latest))
+                            .route("/latest/jobs/{job}", web::get().to(jobset_latest_job))
+                            .route(
+                                "/latest/jobs/{job}/dist/{path:.*}",
+                                web::get().to(jobset_latest_dist),
+                            )
+                            .route("/matrix", web::get().to(jobset_matrix))
+                            .route("/quarantine", web::post().to(jobset_quarantine))
+                            .route("/quarantines", web::get().to(jobset_quarantines))
+                            .route("/set_decl", web::post().to(jobset_set_decl))
+                            .route("/unquarantine", web::post().to(jobset_unquarantine)),
                     ),
             )
             .service(
                 web::scope("/evaluations/{evaluation}")
                     .route("", web::get().to(evaluation_info))
+                    .route("/badge.svg", web::get().to(evaluation_badge))
                     .route("/cancel", web::post().to(evaluation_cancel))
+                    .route("/compare", web::get().to(evaluation_compare))
+                    .route("/compare/{other}", web::get().to(evaluation_compare_with))
+                    .route("/gate", web::get().to(evaluation_gate_info))
+                    .route("/rerun_failed", web::post().to(evaluation_rerun_failed))
                     .route("/log", web::get().to(log_routes::evaluation))
                     .service(
                         web::scope("/jobs/{job}")
@@ -356,7 +770,9 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                             .service(
                                 web::scope("/runs/{run}")
                                     //.route("/cancel", web::post().to(run_cancel))
-                                    .route("", web::get().to(run_info)),
+                                    .route("", web::get().to(run_info))
+                                    .route("/rerun_begin", web::post().to(run_rerun_begin))
+                                    .route("/rerun_end", web::post().to(run_rerun_end)),
                             ),
                     ),
             )
@@ -365,6 +781,11 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                     .route("", web::get().to(action_info))
                     .route("/log", web::get().to(log_routes::action)),
             )
+            .service(
+                web::scope("/deliveries/{delivery}")
+                    .route("", web::get().to(delivery_info))
+                    .route("/replay", web::post().to(delivery_replay)),
+            )
             .route("/login", web::post().to(login))
             .route(
                 "{anything:.*}",
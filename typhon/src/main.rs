@@ -21,6 +21,22 @@ pub struct Args {
     #[arg(long, short, env)]
     pub password: String,
 
+    /// The public URL of the instance, used in statuses reported to forges
+    #[arg(long, env)]
+    pub public_url: Option<String>,
+
+    /// The default maximum duration of builds, in seconds
+    #[arg(long, env)]
+    pub build_timeout: Option<u64>,
+
+    /// The default maximum duration of builds without any output, in seconds
+    #[arg(long, env)]
+    pub max_silent_time: Option<u64>,
+
+    /// The maximum number of builds running at once, unlimited by default
+    #[arg(long, env)]
+    pub max_builds: Option<usize>,
+
     /// Cookie secret
     #[arg(long, value_parser={|s: &str| -> Result<Key, String> {
         if RANDOM_KEY == s {
@@ -46,7 +62,13 @@ async fn main() -> std::io::Result<()> {
 
     let args = Args::parse();
 
-    typhon_core::init(&args.password);
+    typhon_core::init(
+        &args.password,
+        args.public_url.clone(),
+        args.build_timeout,
+        args.max_silent_time,
+        args.max_builds,
+    );
 
     // Run actix server
     let conf = get_configuration(None).await.unwrap();